@@ -0,0 +1,130 @@
+use crate::util::find_project_root;
+use pilcrow::schema::{BindingIssue, BindingSchema};
+use std::path::{Path, PathBuf};
+
+struct Finding {
+    file: String,
+    line: Option<usize>,
+    issue: BindingIssue,
+}
+
+pub fn run(sample: &str, paths: &[String]) -> Result<(), String> {
+    let contents = std::fs::read_to_string(sample)
+        .map_err(|e| format!("Cannot read {sample}: {e}"))?;
+    let value: serde_json::Value = serde_json::from_str(&contents)
+        .map_err(|e| format!("Invalid JSON in {sample}: {e}"))?;
+    let schema = BindingSchema::from_value(&value);
+
+    let roots: Vec<PathBuf> = if paths.is_empty() {
+        vec![find_project_root()?.join("src/presentation/templates")]
+    } else {
+        paths.iter().map(PathBuf::from).collect()
+    };
+
+    let mut findings = Vec::new();
+    let mut scanned = 0;
+
+    for root in &roots {
+        if !root.exists() {
+            return Err(format!("Path not found: {}", root.display()));
+        }
+        for entry in walkdir::WalkDir::new(root)
+            .into_iter()
+            .filter_map(|e| e.ok())
+        {
+            let path = entry.path();
+            if !path.is_file() {
+                continue;
+            }
+            let contents = match std::fs::read_to_string(path) {
+                Ok(c) => c,
+                Err(_) => continue,
+            };
+
+            match path.extension().and_then(|ext| ext.to_str()) {
+                Some("html") | Some("htm") => check_html(&schema, path, &contents, &mut findings),
+                Some("rs") => check_maud(&schema, path, &contents, &mut findings),
+                _ => continue,
+            }
+            scanned += 1;
+        }
+    }
+
+    if findings.is_empty() {
+        println!("Binding check PASSED. {scanned} file(s) scanned, no issues found.");
+        Ok(())
+    } else {
+        for f in &findings {
+            match f.line {
+                Some(line) => eprintln!("\nBINDING {}:{}", f.file, line),
+                None => eprintln!("\nBINDING {}", f.file),
+            }
+            eprintln!("  → {}", f.issue);
+        }
+        eprintln!(
+            "\nFound {} binding issue(s). Binding check FAILED.",
+            findings.len()
+        );
+        std::process::exit(1);
+    }
+}
+
+/// Rendered HTML is checked structurally, so `.field` paths are matched
+/// against the list that owns their template.
+fn check_html(schema: &BindingSchema, path: &Path, contents: &str, findings: &mut Vec<Finding>) {
+    for issue in schema.check(contents) {
+        let needle = format!("{}=\"{}", issue.attribute, issue.path);
+        let line = contents
+            .lines()
+            .position(|l| l.contains(&needle))
+            .map(|i| i + 1);
+        findings.push(Finding {
+            file: path.display().to_string(),
+            line,
+            issue,
+        });
+    }
+}
+
+/// Maud sources are scanned line by line for `s-bind="..."`/`s-list="..."`
+/// literals and `s_bind("...")`/`s_list("...")` helper calls; without the
+/// rendered tree, `.field` paths only need to exist on the items of some
+/// list in the payload.
+fn check_maud(schema: &BindingSchema, path: &Path, contents: &str, findings: &mut Vec<Finding>) {
+    for (line_num, line) in contents.lines().enumerate() {
+        for (attribute, helper) in [("s-bind", "s_bind"), ("s-list", "s_list")] {
+            let literals = attribute_literals(line, &format!("{attribute}=\""))
+                .into_iter()
+                .chain(attribute_literals(line, &format!("{helper}(\"")));
+            for value in literals {
+                if let Some(issue) = schema.check_attribute(attribute, value) {
+                    findings.push(Finding {
+                        file: path.display().to_string(),
+                        line: Some(line_num + 1),
+                        issue,
+                    });
+                }
+            }
+        }
+    }
+}
+
+/// Extract the string literals following `pattern` (which ends in a quote) on one line.
+fn attribute_literals<'a>(line: &'a str, pattern: &str) -> Vec<&'a str> {
+    let mut values = Vec::new();
+    let mut rest = line;
+    while let Some(idx) = rest.find(pattern) {
+        // Skip longer attribute names that merely end in the pattern
+        let boundary = rest[..idx]
+            .chars()
+            .next_back()
+            .is_none_or(|c| !(c.is_alphanumeric() || c == '-' || c == '_'));
+        rest = &rest[idx + pattern.len()..];
+        let Some(end) = rest.find('"') else { break };
+        if boundary {
+            values.push(&rest[..end]);
+        }
+        rest = &rest[end + 1..];
+    }
+    values
+}
@@ -110,7 +110,7 @@ fn silcrow_toml(name: &str) -> String {
 
 use std::sync::Arc;
 use axum::Router;
-use axum::routing::get;
+use silcrow::RouterExt;
 use sqlx::PgPool;
 
 pub struct AppState {
@@ -133,8 +133,8 @@ async fn main() -> anyhow::Result<()> {
     });
 
     let app = Router::new()
-        .route(silcrow::SILCROW_JS_PATH, get(silcrow::serve_silcrow_js))
         .merge(routes::routes(state.clone()))
+        .with_silcrow(silcrow::SilcrowLayer::new())
         .with_state(state);
 
     let addr = format!("{}:{}", config.server.host, config.server.port);
@@ -1,3 +1,4 @@
+mod bindings;
 mod commands;
 mod util;
 mod validate;
@@ -25,6 +26,13 @@ enum Commands {
     },
     /// Validate architectural boundaries
     Validate,
+    /// Check s-bind/s-list paths in templates against a sample payload
+    CheckBindings {
+        /// JSON file holding a representative payload (e.g. `BindingSchema::sample()`)
+        sample: String,
+        /// HTML or Maud template files/directories (default: src/presentation/templates)
+        paths: Vec<String>,
+    },
 }
 
 #[derive(Subcommand)]
@@ -77,6 +85,7 @@ fn main() {
             GenCommands::Migration { name } => commands::gen_migration::run(&name),
         },
         Commands::Validate => validate::run(),
+        Commands::CheckBindings { sample, paths } => bindings::run(&sample, &paths),
     };
 
     if let Err(e) = result {
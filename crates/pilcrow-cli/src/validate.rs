@@ -27,7 +27,7 @@ pub fn run() -> Result<(), String> {
         if !path.is_file() {
             continue;
         }
-        if path.extension().map_or(true, |ext| ext != "rs") {
+        if path.extension().is_none_or(|ext| ext != "rs") {
             continue;
         }
 
@@ -150,20 +150,20 @@ fn check_violation(
                 });
             }
         }
-        "infrastructure" => {
-            // Infrastructure cannot import from presentation or application
+        // Infrastructure cannot import from presentation or application
+        "infrastructure"
             if contains_crate_import(import_line, &[
                 "crate::presentation",
                 "crate::application",
-            ]) {
-                violations.push(Violation {
-                    file: file.into(),
-                    line,
-                    layer: layer.into(),
-                    import: import_line.into(),
-                    reason: "Infrastructure cannot import from presentation or application".into(),
-                });
-            }
+            ]) =>
+        {
+            violations.push(Violation {
+                file: file.into(),
+                line,
+                layer: layer.into(),
+                import: import_line.into(),
+                reason: "Infrastructure cannot import from presentation or application".into(),
+            });
         }
         "presentation" => {
             // Presentation cannot import from infrastructure
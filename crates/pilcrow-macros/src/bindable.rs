@@ -0,0 +1,110 @@
+// ./crates/pilcrow-macros/src/bindable.rs
+
+use proc_macro2::TokenStream;
+use quote::quote;
+use syn::{parse_quote, Data, DeriveInput, Fields};
+
+use crate::serde_attr::{self, apply_rename_all};
+
+enum Override {
+    Scalar,
+    Any,
+    Skip,
+}
+
+fn bind_override(attrs: &[syn::Attribute]) -> syn::Result<Option<Override>> {
+    let mut out = None;
+    for attr in attrs.iter().filter(|a| a.path().is_ident("bind")) {
+        attr.parse_nested_meta(|meta| {
+            if meta.path.is_ident("scalar") {
+                out = Some(Override::Scalar);
+            } else if meta.path.is_ident("any") {
+                out = Some(Override::Any);
+            } else if meta.path.is_ident("skip") {
+                out = Some(Override::Skip);
+            } else {
+                return Err(meta.error("expected `scalar`, `any` or `skip`"));
+            }
+            Ok(())
+        })?;
+    }
+    Ok(out)
+}
+
+pub fn expand(mut input: DeriveInput) -> syn::Result<TokenStream> {
+    let container = serde_attr::container(&input.attrs)?;
+
+    // Every type parameter must itself describe its shape
+    for param in input.generics.type_params_mut() {
+        param.bounds.push(parse_quote!(::pilcrow::schema::Bindable));
+    }
+    let name = &input.ident;
+    let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
+
+    let body = match &input.data {
+        Data::Struct(data) => match &data.fields {
+            Fields::Named(fields) => {
+                let mut inserts = Vec::new();
+                for field in &fields.named {
+                    let serde = serde_attr::field(&field.attrs)?;
+                    let bind = bind_override(&field.attrs)?;
+                    if serde.skip || matches!(bind, Some(Override::Skip)) {
+                        continue;
+                    }
+
+                    let ty = &field.ty;
+                    let schema = match bind {
+                        Some(Override::Scalar) => quote!(::pilcrow::schema::BindingSchema::Scalar),
+                        Some(Override::Any) => quote!(::pilcrow::schema::BindingSchema::Any),
+                        _ => quote!(<#ty as ::pilcrow::schema::Bindable>::binding_schema()),
+                    };
+
+                    if serde.flatten {
+                        inserts.push(quote! { schema = schema.merge(#schema); });
+                        continue;
+                    }
+
+                    let ident = field.ident.as_ref().map(|i| i.to_string()).unwrap_or_default();
+                    let ident = ident.trim_start_matches("r#");
+                    let key = match (&serde.rename, &container.rename_all) {
+                        (Some(rename), _) => rename.clone(),
+                        (None, Some(rule)) => apply_rename_all(rule, ident),
+                        (None, None) => ident.to_string(),
+                    };
+                    inserts.push(quote! { schema.insert_field(#key, #schema); });
+                }
+                quote! {
+                    let mut schema = ::pilcrow::schema::BindingSchema::object();
+                    #(#inserts)*
+                    schema
+                }
+            }
+            // Newtypes serialize as their inner value
+            Fields::Unnamed(fields) if fields.unnamed.len() == 1 => {
+                let ty = &fields.unnamed[0].ty;
+                quote!(<#ty as ::pilcrow::schema::Bindable>::binding_schema())
+            }
+            Fields::Unnamed(_) => quote!(::pilcrow::schema::BindingSchema::Any),
+            Fields::Unit => quote!(::pilcrow::schema::BindingSchema::Scalar),
+        },
+        // Unit-only enums serialize as strings; anything richer is opaque
+        Data::Enum(data) => {
+            if data.variants.iter().all(|v| matches!(v.fields, Fields::Unit)) {
+                quote!(::pilcrow::schema::BindingSchema::Scalar)
+            } else {
+                quote!(::pilcrow::schema::BindingSchema::Any)
+            }
+        }
+        Data::Union(_) => {
+            return Err(syn::Error::new_spanned(name, "Bindable cannot be derived for unions"));
+        }
+    };
+
+    Ok(quote! {
+        impl #impl_generics ::pilcrow::schema::Bindable for #name #ty_generics #where_clause {
+            fn binding_schema() -> ::pilcrow::schema::BindingSchema {
+                #body
+            }
+        }
+    })
+}
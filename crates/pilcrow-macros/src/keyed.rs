@@ -0,0 +1,70 @@
+// ./crates/pilcrow-macros/src/keyed.rs
+
+use proc_macro2::TokenStream;
+use quote::quote;
+use syn::{Data, DeriveInput, LitStr};
+
+pub fn expand(input: DeriveInput) -> syn::Result<TokenStream> {
+    let name = &input.ident;
+    let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
+
+    let Data::Struct(data) = &input.data else {
+        return Err(syn::Error::new_spanned(name, "Keyed can only be derived for structs"));
+    };
+
+    let mut key_field = None;
+    for (index, field) in data.fields.iter().enumerate() {
+        let Some(attr) = field.attrs.iter().find(|a| a.path().is_ident("key")) else {
+            continue;
+        };
+        if key_field.is_some() {
+            return Err(syn::Error::new_spanned(attr, "only one field can be marked #[key]"));
+        }
+
+        // `#[key(template = "row")]` prefixes the key so the runtime picks `<template id="row">`
+        let mut template = None;
+        if !matches!(attr.meta, syn::Meta::Path(_)) {
+            attr.parse_nested_meta(|meta| {
+                if meta.path.is_ident("template") {
+                    let lit: LitStr = meta.value()?.parse()?;
+                    template = Some(lit.value());
+                    Ok(())
+                } else {
+                    Err(meta.error("expected `template = \"...\"`"))
+                }
+            })?;
+        }
+
+        let accessor = match &field.ident {
+            Some(ident) => quote!(self.#ident),
+            None => {
+                let index = syn::Index::from(index);
+                quote!(self.#index)
+            }
+        };
+        key_field = Some((accessor, template));
+    }
+
+    let Some((accessor, template)) = key_field else {
+        return Err(syn::Error::new_spanned(
+            name,
+            "Keyed requires one field marked #[key]",
+        ));
+    };
+
+    let body = match template {
+        Some(template) => {
+            let prefix = format!("{template}#");
+            quote!(::std::format!("{}{}", #prefix, #accessor))
+        }
+        None => quote!(::std::string::ToString::to_string(&#accessor)),
+    };
+
+    Ok(quote! {
+        impl #impl_generics ::pilcrow::keyed::Keyed for #name #ty_generics #where_clause {
+            fn key(&self) -> ::std::string::String {
+                #body
+            }
+        }
+    })
+}
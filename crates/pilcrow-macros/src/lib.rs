@@ -0,0 +1,67 @@
+// ./crates/pilcrow-macros/src/lib.rs
+
+use proc_macro::TokenStream;
+use syn::{parse_macro_input, DeriveInput};
+
+mod bindable;
+mod keyed;
+mod route;
+mod serde_attr;
+mod validate;
+
+/// Derives `pilcrow::schema::Bindable`, describing the JSON shape a type
+/// serializes to so templates can be checked against it.
+///
+/// Honors `#[serde(rename, rename_all, skip, skip_serializing, flatten)]`.
+/// Fields of foreign types without a `Bindable` impl can be annotated with
+/// `#[bind(scalar)]`, `#[bind(any)]` or `#[bind(skip)]`.
+#[proc_macro_derive(Bindable, attributes(bind))]
+pub fn derive_bindable(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+    bindable::expand(input)
+        .unwrap_or_else(syn::Error::into_compile_error)
+        .into()
+}
+
+/// Derives `pilcrow::keyed::Keyed` from the field marked `#[key]`.
+///
+/// The key is the field's `Display` output. `#[key(template = "row")]`
+/// prefixes it with `row#`, which silcrow.js resolves to `<template id="row">`.
+#[proc_macro_derive(Keyed, attributes(key))]
+pub fn derive_keyed(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+    keyed::expand(input)
+        .unwrap_or_else(syn::Error::into_compile_error)
+        .into()
+}
+
+/// Derives `pilcrow::route::Route` from `#[route("/orders/{id}")]` (or
+/// axum's `:id`/`*rest` syntax). Every parameter must name a field and
+/// every field a parameter; fields are written with `Display` and parsed
+/// with `FromStr`.
+///
+/// Also implements `Display` and `Into<String>` (the URL, for `navigate()`,
+/// `s-action` and templates) and `FromRequestParts` (the parsed path). URLs
+/// are relative to the mount point; see `Route::to_path_under`.
+#[proc_macro_derive(Route, attributes(route))]
+pub fn derive_route(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+    route::expand(input)
+        .unwrap_or_else(syn::Error::into_compile_error)
+        .into()
+}
+
+/// Derives `pilcrow::validate::Validate` from `#[validate(...)]` field rules:
+/// `required`, `email`, `length(min = 1, max = 80)`, `range(min = 0, max = 10)`
+/// and `custom = "path::to::fn"` (taking `&FieldType`, returning
+/// `Result<(), String>`). `message = "..."` replaces the messages of every
+/// rule on that field.
+///
+/// Errors are keyed by the field's serde name, matching the form inputs.
+#[proc_macro_derive(Validate, attributes(validate))]
+pub fn derive_validate(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+    validate::expand(input)
+        .unwrap_or_else(syn::Error::into_compile_error)
+        .into()
+}
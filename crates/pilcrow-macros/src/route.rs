@@ -0,0 +1,214 @@
+// ./crates/pilcrow-macros/src/route.rs
+
+use proc_macro2::TokenStream;
+use quote::quote;
+use syn::{Data, DeriveInput, Fields, LitStr};
+
+enum Segment {
+    Static(String),
+    Param(String),
+    /// `*rest` / `{*rest}`: the remainder of the path, slashes included.
+    Wildcard(String),
+}
+
+/// Splits `/orders/:id` or `/orders/{id}` into segments.
+fn parse_path(lit: &LitStr) -> syn::Result<Vec<Segment>> {
+    let path = lit.value();
+    if !path.starts_with('/') {
+        return Err(syn::Error::new_spanned(lit, "route paths must start with `/`"));
+    }
+
+    let raw: Vec<&str> = path[1..].split('/').collect();
+    let mut segments = Vec::new();
+    for (index, segment) in raw.iter().enumerate() {
+        let name = segment
+            .strip_prefix('{')
+            .and_then(|s| s.strip_suffix('}'))
+            .or_else(|| segment.strip_prefix(':'))
+            .or_else(|| segment.strip_prefix('*').map(|_| *segment));
+
+        let Some(name) = name else {
+            if segment.contains(['{', '}', ':', '*']) {
+                return Err(syn::Error::new_spanned(
+                    lit,
+                    format!("parameters must span a whole segment: `{segment}`"),
+                ));
+            }
+            segments.push(Segment::Static(segment.to_string()));
+            continue;
+        };
+
+        let (name, wildcard) = match name.strip_prefix('*') {
+            Some(name) => (name, true),
+            None => (name, false),
+        };
+        if name.is_empty() || !name.chars().all(|c| c.is_alphanumeric() || c == '_') {
+            return Err(syn::Error::new_spanned(
+                lit,
+                format!("invalid parameter name in `{segment}`"),
+            ));
+        }
+        if wildcard && index + 1 != raw.len() {
+            return Err(syn::Error::new_spanned(lit, "a wildcard must be the last segment"));
+        }
+        segments.push(if wildcard {
+            Segment::Wildcard(name.to_string())
+        } else {
+            Segment::Param(name.to_string())
+        });
+    }
+    Ok(segments)
+}
+
+pub fn expand(input: DeriveInput) -> syn::Result<TokenStream> {
+    let name = &input.ident;
+    if !input.generics.params.is_empty() {
+        return Err(syn::Error::new_spanned(&input.generics, "Route cannot be derived for generic types"));
+    }
+
+    let mut path = None;
+    for attr in input.attrs.iter().filter(|a| a.path().is_ident("route")) {
+        if path.is_some() {
+            return Err(syn::Error::new_spanned(attr, "only one #[route(\"...\")] is allowed"));
+        }
+        path = Some(attr.parse_args::<LitStr>()?);
+    }
+    let Some(path) = path else {
+        return Err(syn::Error::new_spanned(name, "Route requires #[route(\"/path/{param}\")]"));
+    };
+    let segments = parse_path(&path)?;
+
+    let Data::Struct(data) = &input.data else {
+        return Err(syn::Error::new_spanned(name, "Route can only be derived for structs"));
+    };
+    let field_names: Vec<&syn::Ident> = match &data.fields {
+        Fields::Named(fields) => fields.named.iter().filter_map(|f| f.ident.as_ref()).collect(),
+        Fields::Unit => Vec::new(),
+        Fields::Unnamed(_) => {
+            return Err(syn::Error::new_spanned(name, "Route requires named fields or a unit struct"));
+        }
+    };
+
+    // Every parameter needs a field and every field a parameter
+    let params: Vec<&str> = segments
+        .iter()
+        .filter_map(|s| match s {
+            Segment::Param(p) | Segment::Wildcard(p) => Some(p.as_str()),
+            Segment::Static(_) => None,
+        })
+        .collect();
+    for param in &params {
+        if !field_names.iter().any(|f| f.to_string().trim_start_matches("r#") == *param) {
+            return Err(syn::Error::new_spanned(
+                &path,
+                format!("path parameter `{param}` has no matching field on `{name}`"),
+            ));
+        }
+    }
+    for field in &field_names {
+        let field_name = field.to_string();
+        if !params.iter().any(|p| *p == field_name.trim_start_matches("r#")) {
+            return Err(syn::Error::new_spanned(
+                field,
+                format!("field `{field_name}` does not appear in the route path"),
+            ));
+        }
+    }
+
+    let field = |param: &str| {
+        *field_names
+            .iter()
+            .find(|f| f.to_string().trim_start_matches("r#") == param)
+            .expect("checked above")
+    };
+
+    // axum 0.7 registration syntax
+    let axum_path: String = segments
+        .iter()
+        .map(|s| match s {
+            Segment::Static(s) => format!("/{s}"),
+            Segment::Param(p) => format!("/:{p}"),
+            Segment::Wildcard(p) => format!("/*{p}"),
+        })
+        .collect();
+
+    let pushes = segments.iter().map(|s| match s {
+        Segment::Static(s) => {
+            let s = format!("/{s}");
+            quote!(path.push_str(#s);)
+        }
+        Segment::Param(p) => {
+            let ident = field(p);
+            quote! {
+                path.push('/');
+                path.push_str(&::pilcrow::route::encode_segment(&self.#ident.to_string()));
+            }
+        }
+        Segment::Wildcard(p) => {
+            let ident = field(p);
+            quote! {
+                path.push('/');
+                path.push_str(&::pilcrow::route::encode_wildcard(&self.#ident.to_string()));
+            }
+        }
+    });
+
+    let parses = params.iter().map(|p| {
+        let ident = field(p);
+        quote!(#ident: params.get(#p)?.parse().ok()?)
+    });
+    let construct = if field_names.is_empty() {
+        quote!(#name)
+    } else {
+        quote!(#name { #(#parses),* })
+    };
+
+    Ok(quote! {
+        impl ::pilcrow::route::Route for #name {
+            const PATH: &'static str = #axum_path;
+
+            fn to_path(&self) -> ::std::string::String {
+                let mut path = ::std::string::String::new();
+                #(#pushes)*
+                if path.is_empty() {
+                    path.push('/');
+                }
+                path
+            }
+
+            #[allow(unused_variables)]
+            fn from_params(
+                params: &::std::collections::HashMap<::std::string::String, ::std::string::String>,
+            ) -> ::std::option::Option<Self> {
+                ::std::option::Option::Some(#construct)
+            }
+        }
+
+        impl ::std::fmt::Display for #name {
+            fn fmt(&self, f: &mut ::std::fmt::Formatter<'_>) -> ::std::fmt::Result {
+                f.write_str(&::pilcrow::route::Route::to_path(self))
+            }
+        }
+
+        impl ::std::convert::From<#name> for ::std::string::String {
+            fn from(route: #name) -> Self {
+                ::pilcrow::route::Route::to_path(&route)
+            }
+        }
+
+        #[::pilcrow::axum::async_trait]
+        impl<S> ::pilcrow::axum::extract::FromRequestParts<S> for #name
+        where
+            S: ::std::marker::Send + ::std::marker::Sync,
+        {
+            type Rejection = ::pilcrow::AppError;
+
+            async fn from_request_parts(
+                parts: &mut ::pilcrow::axum::http::request::Parts,
+                _state: &S,
+            ) -> ::std::result::Result<Self, Self::Rejection> {
+                ::pilcrow::route::extract::<Self>(parts).await
+            }
+        }
+    })
+}
@@ -0,0 +1,119 @@
+// ./crates/pilcrow-macros/src/serde_attr.rs
+
+use syn::meta::ParseNestedMeta;
+use syn::{Attribute, LitStr};
+
+// ════════════════════════════════════════════════════════════
+// 1. The Subset of Serde Attributes That Shapes JSON Keys
+// ════════════════════════════════════════════════════════════
+
+#[derive(Default)]
+pub struct SerdeContainer {
+    pub rename_all: Option<String>,
+}
+
+#[derive(Default)]
+pub struct SerdeField {
+    pub rename: Option<String>,
+    pub skip: bool,
+    pub flatten: bool,
+}
+
+pub fn container(attrs: &[Attribute]) -> syn::Result<SerdeContainer> {
+    let mut out = SerdeContainer::default();
+    for attr in attrs.iter().filter(|a| a.path().is_ident("serde")) {
+        attr.parse_nested_meta(|meta| {
+            if meta.path.is_ident("rename_all") {
+                out.rename_all = serialize_name(&meta)?;
+                Ok(())
+            } else {
+                skip_meta(meta)
+            }
+        })?;
+    }
+    Ok(out)
+}
+
+pub fn field(attrs: &[Attribute]) -> syn::Result<SerdeField> {
+    let mut out = SerdeField::default();
+    for attr in attrs.iter().filter(|a| a.path().is_ident("serde")) {
+        attr.parse_nested_meta(|meta| {
+            if meta.path.is_ident("rename") {
+                out.rename = serialize_name(&meta)?;
+                Ok(())
+            } else if meta.path.is_ident("skip") || meta.path.is_ident("skip_serializing") {
+                out.skip = true;
+                Ok(())
+            } else if meta.path.is_ident("flatten") {
+                out.flatten = true;
+                Ok(())
+            } else {
+                skip_meta(meta)
+            }
+        })?;
+    }
+    Ok(out)
+}
+
+/// Reads `name = "x"` or `name(serialize = "x", deserialize = "y")`.
+fn serialize_name(meta: &ParseNestedMeta) -> syn::Result<Option<String>> {
+    if meta.input.peek(syn::Token![=]) {
+        let lit: LitStr = meta.value()?.parse()?;
+        return Ok(Some(lit.value()));
+    }
+    let mut name = None;
+    meta.parse_nested_meta(|inner| {
+        if inner.path.is_ident("serialize") {
+            let lit: LitStr = inner.value()?.parse()?;
+            name = Some(lit.value());
+            Ok(())
+        } else {
+            skip_meta(inner)
+        }
+    })?;
+    Ok(name)
+}
+
+/// Consumes an attribute we don't care about, whatever its shape.
+pub fn skip_meta(meta: ParseNestedMeta) -> syn::Result<()> {
+    if meta.input.peek(syn::Token![=]) {
+        let _: syn::Lit = meta.value()?.parse()?;
+    } else if meta.input.peek(syn::token::Paren) {
+        meta.parse_nested_meta(skip_meta)?;
+    }
+    Ok(())
+}
+
+// ════════════════════════════════════════════════════════════
+// 2. Serde's `rename_all` Conventions
+// ════════════════════════════════════════════════════════════
+
+/// Applies a `rename_all` rule to a snake_case Rust field name.
+pub fn apply_rename_all(rule: &str, field: &str) -> String {
+    let words = || field.split('_').filter(|w| !w.is_empty());
+    let capitalize = |w: &str| {
+        let mut chars = w.chars();
+        match chars.next() {
+            Some(first) => first.to_uppercase().chain(chars).collect::<String>(),
+            None => String::new(),
+        }
+    };
+
+    match rule {
+        "lowercase" => field.to_lowercase(),
+        "UPPERCASE" => field.to_uppercase(),
+        "PascalCase" => words().map(capitalize).collect(),
+        "camelCase" => {
+            let pascal: String = words().map(capitalize).collect();
+            let mut chars = pascal.chars();
+            match chars.next() {
+                Some(first) => first.to_lowercase().chain(chars).collect(),
+                None => pascal,
+            }
+        }
+        "SCREAMING_SNAKE_CASE" => field.to_uppercase(),
+        "kebab-case" => field.replace('_', "-"),
+        "SCREAMING-KEBAB-CASE" => field.replace('_', "-").to_uppercase(),
+        _ => field.to_string(),
+    }
+}
@@ -0,0 +1,171 @@
+// ./crates/pilcrow-macros/src/validate.rs
+
+use proc_macro2::TokenStream;
+use quote::quote;
+use syn::{Data, DeriveInput, Fields, LitStr};
+
+use crate::serde_attr::{self, apply_rename_all};
+
+// ════════════════════════════════════════════════════════════
+// 1. Field Rules
+// ════════════════════════════════════════════════════════════
+
+enum Rule {
+    Required,
+    Length { min: Option<usize>, max: Option<usize> },
+    Email,
+    Range { min: Option<f64>, max: Option<f64> },
+    Custom(syn::Path),
+}
+
+fn parse_bound<T>(meta: &syn::meta::ParseNestedMeta) -> syn::Result<T>
+where
+    T: std::str::FromStr,
+    T::Err: std::fmt::Display,
+{
+    let lit: syn::Lit = meta.value()?.parse()?;
+    match &lit {
+        syn::Lit::Int(int) => int.base10_parse(),
+        syn::Lit::Float(float) => float.base10_parse(),
+        _ => Err(syn::Error::new_spanned(lit, "expected a number")),
+    }
+}
+
+/// Parses every `#[validate(...)]` on a field into its rules and message override.
+fn field_rules(attrs: &[syn::Attribute]) -> syn::Result<(Vec<Rule>, Option<String>)> {
+    let mut rules = Vec::new();
+    let mut message = None;
+    for attr in attrs.iter().filter(|a| a.path().is_ident("validate")) {
+        attr.parse_nested_meta(|meta| {
+            if meta.path.is_ident("required") {
+                rules.push(Rule::Required);
+            } else if meta.path.is_ident("email") {
+                rules.push(Rule::Email);
+            } else if meta.path.is_ident("length") {
+                let (mut min, mut max) = (None, None);
+                meta.parse_nested_meta(|inner| {
+                    if inner.path.is_ident("min") {
+                        min = Some(parse_bound(&inner)?);
+                    } else if inner.path.is_ident("max") {
+                        max = Some(parse_bound(&inner)?);
+                    } else {
+                        return Err(inner.error("expected `min` or `max`"));
+                    }
+                    Ok(())
+                })?;
+                rules.push(Rule::Length { min, max });
+            } else if meta.path.is_ident("range") {
+                let (mut min, mut max) = (None, None);
+                meta.parse_nested_meta(|inner| {
+                    if inner.path.is_ident("min") {
+                        min = Some(parse_bound(&inner)?);
+                    } else if inner.path.is_ident("max") {
+                        max = Some(parse_bound(&inner)?);
+                    } else {
+                        return Err(inner.error("expected `min` or `max`"));
+                    }
+                    Ok(())
+                })?;
+                rules.push(Rule::Range { min, max });
+            } else if meta.path.is_ident("custom") {
+                let lit: LitStr = meta.value()?.parse()?;
+                rules.push(Rule::Custom(lit.parse()?));
+            } else if meta.path.is_ident("message") {
+                let lit: LitStr = meta.value()?.parse()?;
+                message = Some(lit.value());
+            } else {
+                return Err(meta.error(
+                    "expected `required`, `email`, `length(..)`, `range(..)`, `custom = \"..\"` or `message = \"..\"`",
+                ));
+            }
+            Ok(())
+        })?;
+    }
+    Ok((rules, message))
+}
+
+fn option_tokens<T: quote::ToTokens>(value: Option<T>) -> TokenStream {
+    match value {
+        Some(value) => quote!(::std::option::Option::Some(#value)),
+        None => quote!(::std::option::Option::None),
+    }
+}
+
+// ════════════════════════════════════════════════════════════
+// 2. Expansion
+// ════════════════════════════════════════════════════════════
+
+pub fn expand(input: DeriveInput) -> syn::Result<TokenStream> {
+    let container = serde_attr::container(&input.attrs)?;
+    let name = &input.ident;
+    let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
+
+    let Data::Struct(data) = &input.data else {
+        return Err(syn::Error::new_spanned(name, "Validate can only be derived for structs"));
+    };
+    let Fields::Named(fields) = &data.fields else {
+        return Err(syn::Error::new_spanned(name, "Validate requires named fields"));
+    };
+
+    let mut keys = Vec::new();
+    let mut checks = Vec::new();
+    for field in &fields.named {
+        let serde = serde_attr::field(&field.attrs)?;
+        let (rules, message) = field_rules(&field.attrs)?;
+        if serde.skip || serde.flatten {
+            continue;
+        }
+
+        // Errors are keyed like the form inputs, i.e. by the serde name
+        let ident = field.ident.as_ref().expect("named field");
+        let raw = ident.to_string();
+        let raw = raw.trim_start_matches("r#");
+        let key = match (&serde.rename, &container.rename_all) {
+            (Some(rename), _) => rename.clone(),
+            (None, Some(rule)) => apply_rename_all(rule, raw),
+            (None, None) => raw.to_string(),
+        };
+        keys.push(key.clone());
+
+        for rule in rules {
+            let check = match rule {
+                Rule::Required => quote!(::pilcrow::validate::rules::required(&self.#ident)),
+                Rule::Email => quote!(::pilcrow::validate::rules::email(&self.#ident)),
+                Rule::Length { min, max } => {
+                    let (min, max) = (option_tokens(min), option_tokens(max));
+                    quote!(::pilcrow::validate::rules::length(&self.#ident, #min, #max))
+                }
+                Rule::Range { min, max } => {
+                    let (min, max) = (option_tokens(min), option_tokens(max));
+                    quote!(::pilcrow::validate::rules::range(&self.#ident, #min, #max))
+                }
+                Rule::Custom(path) => quote!(#path(&self.#ident)),
+            };
+            let check = match &message {
+                Some(message) => quote!((#check).map_err(|_| ::std::string::String::from(#message))),
+                None => check,
+            };
+            checks.push(quote! {
+                if let ::std::result::Result::Err(message) = #check {
+                    errors.add(#key, message);
+                }
+            });
+        }
+    }
+
+    Ok(quote! {
+        impl #impl_generics ::pilcrow::validate::Validate for #name #ty_generics #where_clause {
+            fn fields() -> &'static [&'static str] {
+                &[#(#keys),*]
+            }
+
+            fn validate(&self) -> ::std::result::Result<(), ::pilcrow::validate::ValidationErrors> {
+                let mut errors = ::pilcrow::validate::ValidationErrors::new(
+                    <Self as ::pilcrow::validate::Validate>::fields(),
+                );
+                #(#checks)*
+                errors.into_result()
+            }
+        }
+    })
+}
@@ -0,0 +1,42 @@
+// ./crates/pilcrow/build.rs
+
+use std::{env, fs, io::Write, path::Path};
+
+use base64::Engine;
+use sha2::{Digest, Sha384};
+
+fn main() {
+    println!("cargo:rerun-if-changed=public/silcrow.js");
+
+    let js = fs::read("public/silcrow.js").expect("public/silcrow.js is missing");
+    let out_dir = env::var("OUT_DIR").expect("OUT_DIR is set by cargo");
+    let out = Path::new(&out_dir);
+
+    // Subresource Integrity hash for the embedded runtime
+    let digest = Sha384::digest(&js);
+    let integrity = format!(
+        "sha384-{}",
+        base64::engine::general_purpose::STANDARD.encode(digest)
+    );
+    write(out, "silcrow_integrity.txt", integrity.as_bytes());
+
+    // Short content hash for the cache-busting URL
+    let hash: String = digest[..6].iter().map(|b| format!("{b:02x}")).collect();
+    write(out, "silcrow_hash.txt", hash.as_bytes());
+
+    // Precompressed variants, chosen at request time via Accept-Encoding
+    let mut gzip = flate2::write::GzEncoder::new(Vec::new(), flate2::Compression::best());
+    gzip.write_all(&js).expect("gzip failed");
+    write(out, "silcrow.js.gz", &gzip.finish().expect("gzip failed"));
+
+    let mut brotli = Vec::new();
+    {
+        let mut writer = brotli::CompressorWriter::new(&mut brotli, 4096, 11, 22);
+        writer.write_all(&js).expect("brotli failed");
+    }
+    write(out, "silcrow.js.br", &brotli);
+}
+
+fn write(out: &Path, name: &str, contents: &[u8]) {
+    fs::write(out.join(name), contents).unwrap_or_else(|e| panic!("failed to write {name}: {e}"));
+}
@@ -153,6 +153,13 @@
     const node = elements[0];
 
     // Cache local bindings on the node itself
+    localBindingsCache.set(node, collectLocalBindings(node));
+
+    registerSubtreeBindings(node, scalarMap);
+    return node;
+  }
+
+  function collectLocalBindings(node) {
     const localBindings = new Map();
 
     if (node.hasAttribute("s-bind")) {
@@ -178,11 +185,7 @@
       }
     }
 
-    // Store in WeakMap
-    localBindingsCache.set(node, localBindings);
-
-    registerSubtreeBindings(node, scalarMap);
-    return node;
+    return localBindings;
   }
 
 
@@ -295,8 +298,12 @@
   }
 
   function patchItem(node, item) {
-    const bindings = localBindingsCache.get(node);
-    if (!bindings) return;
+    let bindings = localBindingsCache.get(node);
+    if (!bindings) {
+      // Server-rendered items (pilcrow::render) were never cloned here
+      bindings = collectLocalBindings(node);
+      localBindingsCache.set(node, bindings);
+    }
 
     for (const field in item) {
       if (field === "key") continue;
@@ -353,7 +360,7 @@
     return {scalarMap, collectionMap};
   }
 
-  function applyPatch(data, scalarMap, collectionMap) {
+  function applyPatch(data, scalarMap, collectionMap, partial = false) {
     for (const [path, bindings] of scalarMap.entries()) {
       const value = resolvePath(data, path);
       if (value !== undefined) {
@@ -367,6 +374,9 @@
       const value = resolvePath(data, path);
       if (Array.isArray(value)) {
         reconcile(container, value, resolveTemplate);
+      } else if (value === null && partial) {
+        // Merge patches signal a removed collection with null
+        reconcile(container, [], resolveTemplate);
       } else if (value !== undefined && DEBUG) {
         warn("Collection value is not an array: " + path);
       }
@@ -397,12 +407,12 @@
       instanceCache.set(element, instance);
     }
 
-    applyPatch(data, instance.scalarMap, instance.collectionMap);
+    applyPatch(data, instance.scalarMap, instance.collectionMap, !!options.partial);
     // Emit custom event after patching
     if (!options.silent) {
       element.dispatchEvent(new CustomEvent('silcrow:patched', {
         bubbles: true,
-        detail: {paths: Array.from(instance.scalarMap.keys())}
+        detail: {paths: Array.from(instance.scalarMap.keys()), partial: !!options.partial}
       }));
     }
   }
@@ -439,15 +449,110 @@
   // ════════════════════════════════════════════════════════════
 
   const HTTP_METHODS = ["DELETE", "PUT", "POST", "PATCH", "GET"];
-  const DEFAULT_TIMEOUT = 30000; // 30s
 
-  const CACHE_TTL = 5 * 60 * 1000; // 5 minutes
-  const MAX_CACHE = 50;
+  // Overridable from the script tag (data-timeout, data-cache-ttl, data-max-cache)
+  const SCRIPT_EL = document.currentScript;
+  function scriptOption(name, fallback) {
+    const val = parseInt(SCRIPT_EL?.dataset[name] ?? "", 10);
+    return Number.isFinite(val) && val >= 0 ? val : fallback;
+  }
+
+  const DEFAULT_TIMEOUT = scriptOption("timeout", 30000); // 30s
+  const CACHE_TTL = scriptOption("cacheTtl", 5 * 60 * 1000); // 5 minutes
+  const MAX_CACHE = scriptOption("maxCache", 50);
+
+  // Echoed in silcrow-runtime so the server can force a reload after a deploy
+  const RUNTIME_TOKEN =
+    SCRIPT_EL?.dataset.runtime ||
+    (SCRIPT_EL?.src.match(/silcrow\.([0-9a-f]+)\.js/) || [])[1] ||
+    null;
+
+  function runtimeHeaders(headers) {
+    if (RUNTIME_TOKEN) headers["silcrow-runtime"] = RUNTIME_TOKEN;
+    return headers;
+  }
+
+  // Double-submit CSRF token issued by pilcrow's CsrfLayer
+  function csrfToken() {
+    const match = document.cookie.match(/(?:^|;\s*)silcrow_csrf=([^;]*)/);
+    return match ? decodeURIComponent(match[1]) : null;
+  }
+
+  // fetch() exposes no upload progress, so bodies carrying files go through XHR
+  function hasFiles(body) {
+    if (!(body instanceof FormData)) return false;
+    for (const value of body.values()) {
+      if (value instanceof File && value.name) return true;
+    }
+    return false;
+  }
+
+  function xhrFetch(url, options, sourceEl) {
+    return new Promise((resolve, reject) => {
+      const xhr = new XMLHttpRequest();
+      xhr.open(options.method, url);
+      for (const [name, value] of Object.entries(options.headers || {})) {
+        xhr.setRequestHeader(name, value);
+      }
+      xhr.responseType = "text";
+
+      xhr.upload.onprogress = (e) => {
+        (sourceEl || document).dispatchEvent(
+          new CustomEvent("silcrow:upload-progress", {
+            bubbles: true,
+            detail: {
+              loaded: e.loaded,
+              total: e.lengthComputable ? e.total : null,
+              percent: e.lengthComputable ? Math.round((e.loaded / e.total) * 100) : null,
+              form: sourceEl,
+            },
+          })
+        );
+      };
+
+      xhr.onload = () => {
+        const headers = new Headers();
+        for (const line of xhr.getAllResponseHeaders().trim().split(/[\r\n]+/)) {
+          const idx = line.indexOf(":");
+          if (idx > 0) headers.append(line.slice(0, idx).trim(), line.slice(idx + 1).trim());
+        }
+        const status = xhr.status;
+        const response = new Response(status === 204 || status === 304 ? null : xhr.responseText, {
+          status,
+          statusText: xhr.statusText,
+          headers,
+        });
+        const finalUrl = xhr.responseURL || url;
+        Object.defineProperty(response, "url", {value: finalUrl});
+        Object.defineProperty(response, "redirected", {value: finalUrl !== url});
+        resolve(response);
+      };
+      xhr.onerror = () => reject(new TypeError("Network request failed"));
+      xhr.onabort = () => reject(new DOMException("Aborted", "AbortError"));
+
+      if (options.signal) {
+        if (options.signal.aborted) return reject(new DOMException("Aborted", "AbortError"));
+        options.signal.addEventListener("abort", () => xhr.abort(), {once: true});
+      }
+      xhr.send(options.body);
+    });
+  }
+
+  // One key per submission of an element, kept until a response arrives, so
+  // double submits and retries after network failures share it
+  const idempotencyKeys = new WeakMap();
+  function newIdempotencyKey() {
+    if (crypto.randomUUID) return crypto.randomUUID();
+    const bytes = crypto.getRandomValues(new Uint8Array(16));
+    return Array.from(bytes, (b) => b.toString(16).padStart(2, "0")).join("");
+  }
+
   const abortMap = new WeakMap();
   let routeHandler = null;
   let errorHandler = null;
   const responseCache = new Map();
   const preloadInflight = new Map();
+  const versionMap = new WeakMap();
   let toastHandler = null;
 
   // Internal function to check for and trigger toasts
@@ -541,6 +646,27 @@
     return html;
   }
 
+  // ── Document Head ──────────────────────────────────────────
+  function applyHead(encoded) {
+    let head;
+    try {
+      head = JSON.parse(decodeURIComponent(encoded));
+    } catch (e) {
+      warn("Ignoring malformed silcrow-head header");
+      return;
+    }
+
+    if (typeof head.title === "string") document.title = head.title;
+
+    // Page-owned nodes carry data-silcrow-head; the layout's own are kept
+    for (const node of document.head.querySelectorAll("[data-silcrow-head]")) {
+      node.remove();
+    }
+    const tpl = document.createElement("template");
+    tpl.innerHTML = head.html || "";
+    document.head.append(tpl.content);
+  }
+
   // ── Safe HTML Assignment ───────────────────────────────────
   function safeSetHTML(el, html) {
     if (el.setHTML) {
@@ -550,6 +676,70 @@
     }
   }
 
+  // ── Out-of-Band Swaps ──────────────────────────────────────
+  function collectSwaps(root) {
+    const swaps = [];
+    for (const tpl of root.querySelectorAll("template[s-swap]")) {
+      swaps.push({
+        selector: tpl.getAttribute("s-swap"),
+        mode: tpl.getAttribute("s-swap-mode") || "inner",
+        html: tpl.innerHTML,
+      });
+      tpl.remove();
+    }
+    return swaps;
+  }
+
+  function extractSwaps(html) {
+    if (!html.includes("s-swap")) return {html, swaps: []};
+
+    const trimmed = html.trimStart();
+    if (trimmed.startsWith("<!") || trimmed.startsWith("<html")) {
+      const doc = new DOMParser().parseFromString(html, "text/html");
+      const swaps = collectSwaps(doc);
+      return {html: "<!DOCTYPE html>" + doc.documentElement.outerHTML, swaps};
+    }
+
+    const tpl = document.createElement("template");
+    tpl.innerHTML = html;
+    const swaps = collectSwaps(tpl.content);
+    return {html: tpl.innerHTML, swaps};
+  }
+
+  function applySwaps(swaps) {
+    for (const {selector, mode, html} of swaps) {
+      let el = null;
+      try {
+        el = document.querySelector(selector);
+      } catch (e) {
+        warn("Invalid swap selector: " + selector);
+        continue;
+      }
+      if (!el) {
+        warn("Swap target not found: " + selector);
+        continue;
+      }
+
+      if (mode === "remove") {
+        el.remove();
+        continue;
+      }
+      if (mode === "inner") {
+        safeSetHTML(el, html);
+        continue;
+      }
+
+      const holder = document.createElement("div");
+      safeSetHTML(holder, html);
+      const nodes = Array.from(holder.childNodes);
+
+      if (mode === "outer") el.replaceWith(...nodes);
+      else if (mode === "append") el.append(...nodes);
+      else if (mode === "prepend") el.prepend(...nodes);
+      else warn("Unknown swap mode: " + mode);
+    }
+  }
+
   // ── Loading State ──────────────────────────────────────────
   function showLoading(el) {
     el.classList.add("silcrow-loading");
@@ -562,6 +752,15 @@
   }
 
   // ── Cache Management ───────────────────────────────────────
+  // Targeted requests may get a fragment, so they're cached per selector
+  function cacheKey(url, selector) {
+    return selector ? `${url} ${selector}` : url;
+  }
+
+  function targetHeader(selector) {
+    return selector ? encodeURIComponent(selector) : "true";
+  }
+
   function cacheSet(url, entry) {
     responseCache.set(url, entry);
     if (responseCache.size > MAX_CACHE) {
@@ -625,23 +824,45 @@
     showLoading(targetEl);
 
     try {
-      let cached = method === "GET" ? cacheGet(fullUrl) : null;
+      const key = cacheKey(fullUrl, targetSelector);
+      let cached = method === "GET" ? cacheGet(key) : null;
 
       let text, contentType, redirected = false, finalUrl = fullUrl;
+      let version = null, patchMode = null, head = null;
       const wantsHTML = sourceEl?.hasAttribute("s-html");
       if (cached) {
         text = cached.text;
         contentType = cached.contentType;
+        version = cached.version || null;
+        head = cached.head || null;
       } else {
         const fetchOptions = {
           method,
-          headers: {
-            "silcrow-target": "true",
+          headers: runtimeHeaders({
+            "silcrow-target": targetHeader(targetSelector),
             "Accept": wantsHTML ? "text/html" : "application/json",
-          },
+          }),
           signal: controller.signal,
         };
 
+        // Ask for a diff against the snapshot this target already shows
+        const heldVersion = versionMap.get(targetEl);
+        if (method === "GET" && !wantsHTML && heldVersion) {
+          fetchOptions.headers["silcrow-patch-base"] = heldVersion;
+        }
+
+        if (method !== "GET") {
+          const csrf = csrfToken();
+          if (csrf) fetchOptions.headers["silcrow-csrf"] = csrf;
+
+          const idempotencyKey =
+            options.idempotencyKey ||
+            (sourceEl && idempotencyKeys.get(sourceEl)) ||
+            newIdempotencyKey();
+          if (sourceEl) idempotencyKeys.set(sourceEl, idempotencyKey);
+          fetchOptions.headers["Idempotency-Key"] = idempotencyKey;
+        }
+
         if (body) {
           if (body instanceof FormData) {
             fetchOptions.body = body;
@@ -651,9 +872,35 @@
           }
         }
 
-        const response = await fetch(fullUrl, fetchOptions);
+        let response = hasFiles(fetchOptions.body)
+          ? await xhrFetch(fullUrl, fetchOptions, sourceEl)
+          : await fetch(fullUrl, fetchOptions);
+
+        // Partial patch against a snapshot we no longer hold: refetch in full
+        if (
+          response.ok &&
+          response.headers.get("silcrow-patch") === "merge" &&
+          response.headers.get("silcrow-patch-base") !== versionMap.get(targetEl)
+        ) {
+          warn("Patch base mismatch, refetching full payload");
+          delete fetchOptions.headers["silcrow-patch-base"];
+          response = await fetch(fullUrl, fetchOptions);
+        }
+
+        // Server runs a newer runtime or app version: start over with a real load
+        if (response.headers.get("silcrow-reload")) {
+          if (method === "GET" && shouldPushHistory) location.assign(fullUrl);
+          else location.reload();
+          return;
+        }
+
+        // A duplicate of a submission still in flight: the original will do the swap
+        if (response.status === 409 && response.headers.get("silcrow-duplicate")) return;
+        if (method !== "GET" && sourceEl) idempotencyKeys.delete(sourceEl);
 
-        if (!response.ok) {
+        // 422 carries the re-rendered form (or field errors) and is swapped like a success
+        const invalid = response.status === 422;
+        if (!response.ok && !invalid) {
           throw new Error(`HTTP ${response.status}: ${response.statusText}`);
         }
 
@@ -663,13 +910,17 @@
 
         text = await response.text();
         contentType = response.headers.get("Content-Type") || "";
+        version = response.headers.get("silcrow-version");
+        patchMode = response.headers.get("silcrow-patch");
+        head = response.headers.get("silcrow-head");
 
+        // Partial patches only make sense against the base they were cut from
         const cacheControl = response.headers.get("silcrow-cache");
-        if (method === "GET" && !redirected && cacheControl !== "no-cache") {
-          cacheSet(fullUrl, {text, contentType, ts: Date.now()});
+        if (method === "GET" && !invalid && !redirected && cacheControl !== "no-cache" && !patchMode) {
+          cacheSet(key, {text, contentType, version, head, ts: Date.now()});
         }
 
-        if (method !== "GET") {
+        if (method !== "GET" && !invalid) {
           bustCacheOnMutation();
         }
       }
@@ -704,6 +955,7 @@
 
       // Prepare swap content
       let swapContent;
+      let swaps = [];
       const isJSON = contentType.includes("application/json");
 
       if (isJSON) {
@@ -711,7 +963,9 @@
         processToasts(true, swapContent); // <-- ADD THIS HERE
       } else {
         const isFullPage = !targetSelector;
-        swapContent = extractHTML(text, targetSelector, isFullPage);
+        const extracted = extractSwaps(text);
+        swaps = extracted.swaps;
+        swapContent = extractHTML(extracted.html, targetSelector, isFullPage);
         processToasts(false); // <-- ADD THIS HERE
       }
 
@@ -721,10 +975,16 @@
         if (swapExecuted) return;
         swapExecuted = true;
         if (isJSON) {
-          patch(swapContent, targetEl);
+          // Merge patches only carry changed paths; applyPatch skips the rest
+          patch(swapContent, targetEl, {partial: patchMode === "merge"});
+          if (version) versionMap.set(targetEl, version);
+          else versionMap.delete(targetEl);
         } else {
           safeSetHTML(targetEl, swapContent);
+          applySwaps(swaps);
         }
+        // Page metadata only follows full-page navigations
+        if (head && !targetSelector) applyHead(head);
       };
 
       const beforeSwap = new CustomEvent("silcrow:before-swap", {
@@ -734,6 +994,7 @@
           url: finalUrl,
           target: targetEl,
           content: swapContent,
+          swaps,
           isJSON,
           proceed,
         },
@@ -822,7 +1083,7 @@
     const fullUrl = resolveUrl(el);
     if (!fullUrl) return;
 
-    const inflight = preloadInflight.get(fullUrl);
+    const inflight = preloadInflight.get(cacheKey(fullUrl, el.getAttribute("s-target")));
     if (inflight) await inflight;
 
     navigate(fullUrl, {
@@ -892,28 +1153,35 @@
     if (!el) return;
 
     const fullUrl = resolveUrl(el);
-    if (!fullUrl || responseCache.has(fullUrl) || preloadInflight.has(fullUrl)) return;
+    if (!fullUrl) return;
+    const selector = el.getAttribute("s-target");
+    const key = cacheKey(fullUrl, selector);
+    if (responseCache.has(key) || preloadInflight.has(key)) return;
     const controller = new AbortController();
     const wantsHTML = el.hasAttribute("s-html");
     const promise = fetch(fullUrl, {
-      headers: {"silcrow-target": "true", "Accept": wantsHTML ? "text/html" : "application/json"},
+      headers: runtimeHeaders({
+        "silcrow-target": targetHeader(selector),
+        "Accept": wantsHTML ? "text/html" : "application/json",
+      }),
       signal: controller.signal,
     })
       .then((r) => {
-        if (!r.ok) throw new Error(`HTTP ${r.status}`);
+        if (!r.ok || r.headers.get("silcrow-reload")) throw new Error(`HTTP ${r.status}`);
         const contentType = r.headers.get("Content-Type") || "";
         const cacheControl = r.headers.get("silcrow-cache");
-        return r.text().then((text) => ({text, contentType, cacheControl}));
+        const head = r.headers.get("silcrow-head");
+        return r.text().then((text) => ({text, contentType, cacheControl, head}));
       })
-      .then(({text, contentType, cacheControl}) => {
+      .then(({text, contentType, cacheControl, head}) => {
         if (cacheControl !== "no-cache") {
-          cacheSet(fullUrl, {text, contentType, ts: Date.now()});
+          cacheSet(key, {text, contentType, head, ts: Date.now()});
         }
       })
       .catch(() => {})
-      .finally(() => preloadInflight.delete(fullUrl));
+      .finally(() => preloadInflight.delete(key));
 
-    preloadInflight.set(fullUrl, promise);
+    preloadInflight.set(key, promise);
   }
 
   // ── Init & Teardown ────────────────────────────────────────
@@ -964,6 +1232,7 @@
         target: options.target
           ? document.querySelector(options.target)
           : null,
+        idempotencyKey: options.idempotencyKey,
         trigger: "api",
       });
     },
@@ -982,7 +1251,9 @@
       clear(path) {
         if (path) {
           const url = new URL(path, location.origin).href;
-          responseCache.delete(url);
+          for (const key of [...responseCache.keys()]) {
+            if (key === url || key.startsWith(`${url} `)) responseCache.delete(key);
+          }
         } else {
           responseCache.clear();
         }
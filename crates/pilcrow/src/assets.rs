@@ -1,31 +1,282 @@
 // ./crates/pilcrow/src/assets.rs
 
-use axum::http::{header, StatusCode};
+use std::fmt;
+use std::time::Duration;
+
+use axum::http::{header, HeaderMap, HeaderValue, StatusCode, Uri};
 use axum::response::{IntoResponse, Response};
+use axum::routing::get;
+use axum::Router;
+
+use crate::response::escape_attr;
+use crate::version::RuntimeVersion;
 
 /// The unified Silcrow client runtime, embedded at compile time.
 pub const SILCROW_JS: &str = include_str!("../public/silcrow.js");
 
-/// Canonical URL path for serving the Silcrow JS bundle.
+/// Subresource Integrity hash (`sha384-...`) of [`SILCROW_JS`], computed at build time.
+pub const SILCROW_JS_INTEGRITY: &str = include_str!(concat!(env!("OUT_DIR"), "/silcrow_integrity.txt"));
+
+/// Short content hash of [`SILCROW_JS`], part of its cache-busting URL.
+pub const SILCROW_JS_HASH: &str = include_str!(concat!(env!("OUT_DIR"), "/silcrow_hash.txt"));
+
+/// Build-time gzip and brotli variants of [`SILCROW_JS`].
+pub const SILCROW_JS_GZIP: &[u8] = include_bytes!(concat!(env!("OUT_DIR"), "/silcrow.js.gz"));
+pub const SILCROW_JS_BROTLI: &[u8] = include_bytes!(concat!(env!("OUT_DIR"), "/silcrow.js.br"));
+
+/// Fixed URL path of the Silcrow JS bundle; redirects to the hashed URL.
 pub const SILCROW_JS_PATH: &str = "/_silcrow/silcrow.js";
 
-/// Axum handler that serves the embedded Silcrow JS with aggressive caching.
-pub async fn serve_silcrow_js() -> Response {
-    (
+/// Returns the content-hashed path (`/_silcrow/silcrow.<hash>.js`).
+pub fn silcrow_js_hashed_path() -> String {
+    format!("/_silcrow/{}", hashed_file_name())
+}
+
+fn hashed_file_name() -> String {
+    format!("silcrow.{SILCROW_JS_HASH}.js")
+}
+
+/// Routes for both the hashed bundle and the fixed compatibility path.
+pub fn routes<S: Clone + Send + Sync + 'static>() -> Router<S> {
+    Router::new()
+        .route(SILCROW_JS_PATH, get(serve_silcrow_js))
+        .route(&silcrow_js_hashed_path(), get(serve_silcrow_js))
+}
+
+/// Axum handler for the Silcrow JS bundle.
+///
+/// The hashed URL is served precompressed with a one-year `immutable` cache
+/// and an ETag. Any other path (the fixed [`SILCROW_JS_PATH`]) answers with a
+/// short-lived redirect, so upgrades reach browsers within minutes.
+pub async fn serve_silcrow_js(uri: Uri, headers: HeaderMap) -> Response {
+    let file = hashed_file_name();
+    if !uri.path().ends_with(&file) {
+        // Relative, so the redirect keeps any mount prefix
+        return (
+            StatusCode::FOUND,
+            [
+                (header::LOCATION, file),
+                (header::CACHE_CONTROL, "public, max-age=300".to_string()),
+            ],
+        )
+            .into_response();
+    }
+
+    let encoding = Encoding::negotiate(headers.get(header::ACCEPT_ENCODING));
+    let etag = format!("\"{SILCROW_JS_HASH}{}\"", encoding.etag_suffix());
+    let cache_headers = [
+        (header::ETAG, etag.clone()),
+        (header::CACHE_CONTROL, "public, max-age=31536000, immutable".to_string()),
+        (header::VARY, "Accept-Encoding".to_string()),
+    ];
+
+    let not_modified = headers
+        .get(header::IF_NONE_MATCH)
+        .and_then(|v| v.to_str().ok())
+        .is_some_and(|v| etag_matches(v, &etag));
+    if not_modified {
+        return (StatusCode::NOT_MODIFIED, cache_headers).into_response();
+    }
+
+    let mut response = (
         StatusCode::OK,
-        [
-            (
-                header::CONTENT_TYPE,
-                "application/javascript; charset=utf-8",
-            ),
-            (header::CACHE_CONTROL, "public, max-age=31536000, immutable"),
-        ],
-        SILCROW_JS,
+        cache_headers,
+        [(
+            header::CONTENT_TYPE,
+            "application/javascript; charset=utf-8",
+        )],
+        encoding.body(),
     )
-        .into_response()
+        .into_response();
+    if let Some(name) = encoding.header_value() {
+        response
+            .headers_mut()
+            .insert(header::CONTENT_ENCODING, HeaderValue::from_static(name));
+    }
+    response
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Encoding {
+    Brotli,
+    Gzip,
+    Identity,
+}
+
+impl Encoding {
+    /// Picks brotli, then gzip, honoring `q=0` refusals and `*`.
+    fn negotiate(accept: Option<&HeaderValue>) -> Self {
+        let accept = accept.and_then(|v| v.to_str().ok()).unwrap_or("");
+        let accepted = |name: &str| {
+            let mut wildcard = false;
+            for entry in accept.split(',') {
+                let mut parts = entry.split(';');
+                let coding = parts.next().unwrap_or("").trim();
+                let refused = parts.any(|p| {
+                    p.trim()
+                        .strip_prefix("q=")
+                        .and_then(|q| q.trim().parse::<f32>().ok())
+                        .is_some_and(|q| q <= 0.0)
+                });
+                if coding.eq_ignore_ascii_case(name) {
+                    return !refused;
+                }
+                if coding == "*" {
+                    wildcard = !refused;
+                }
+            }
+            wildcard
+        };
+
+        if accepted("br") {
+            Encoding::Brotli
+        } else if accepted("gzip") {
+            Encoding::Gzip
+        } else {
+            Encoding::Identity
+        }
+    }
+
+    fn body(self) -> &'static [u8] {
+        match self {
+            Encoding::Brotli => SILCROW_JS_BROTLI,
+            Encoding::Gzip => SILCROW_JS_GZIP,
+            Encoding::Identity => SILCROW_JS.as_bytes(),
+        }
+    }
+
+    fn header_value(self) -> Option<&'static str> {
+        match self {
+            Encoding::Brotli => Some("br"),
+            Encoding::Gzip => Some("gzip"),
+            Encoding::Identity => None,
+        }
+    }
+
+    fn etag_suffix(self) -> &'static str {
+        match self {
+            Encoding::Brotli => "-br",
+            Encoding::Gzip => "-gz",
+            Encoding::Identity => "",
+        }
+    }
+}
+
+fn etag_matches(if_none_match: &str, etag: &str) -> bool {
+    if_none_match.split(',').any(|candidate| {
+        let candidate = candidate.trim();
+        candidate == "*" || candidate.trim_start_matches("W/") == etag
+    })
+}
+
+/// Returns the default `<script>` tag pointing to the Silcrow JS bundle.
+///
+/// It knows no app version; [`SilcrowLayer`](crate::SilcrowLayer) fills in the
+/// configured one when the document passes through it.
+pub fn script_tag() -> String {
+    ScriptTag::new().to_string()
+}
+
+// ════════════════════════════════════════════════════════════
+// Script Tag Builder
+// ════════════════════════════════════════════════════════════
+
+/// Builds the `<script>` tag that loads silcrow.js.
+///
+/// Renders with `Display`. Runtime options become `data-*` attributes that
+/// silcrow.js reads on startup instead of its built-in defaults.
+#[derive(Debug, Clone, Default)]
+pub struct ScriptTag {
+    base_path: Option<String>,
+    nonce: Option<String>,
+    integrity: bool,
+    timeout: Option<Duration>,
+    cache_ttl: Option<Duration>,
+    max_cache: Option<usize>,
+    runtime: RuntimeVersion,
+}
+
+impl ScriptTag {
+    pub fn new() -> Self {
+        ScriptTag {
+            integrity: true,
+            ..Default::default()
+        }
+    }
+
+    /// Mount prefix the asset route lives under, e.g. `/app`.
+    pub fn base_path(mut self, base_path: impl Into<String>) -> Self {
+        self.base_path = Some(base_path.into());
+        self
+    }
+
+    /// Per-request CSP nonce.
+    pub fn nonce(mut self, nonce: impl Into<String>) -> Self {
+        self.nonce = Some(nonce.into());
+        self
+    }
+
+    /// Omits the `integrity` attribute, e.g. when a proxy rewrites the bundle.
+    pub fn without_integrity(mut self) -> Self {
+        self.integrity = false;
+        self
+    }
+
+    /// Default request timeout (30s).
+    pub fn timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = Some(timeout);
+        self
+    }
+
+    /// How long GET responses stay in the client cache (5 minutes).
+    pub fn cache_ttl(mut self, ttl: Duration) -> Self {
+        self.cache_ttl = Some(ttl);
+        self
+    }
+
+    /// Maximum number of cached responses (50).
+    pub fn max_cache(mut self, entries: usize) -> Self {
+        self.max_cache = Some(entries);
+        self
+    }
+
+    /// Application version echoed back for the reload handshake; must match
+    /// the one given to [`VersionCheckLayer`](crate::VersionCheckLayer).
+    pub fn app_version(mut self, version: impl Into<String>) -> Self {
+        self.runtime = RuntimeVersion::new().app(version);
+        self
+    }
+
+    pub(crate) fn runtime(&self) -> &RuntimeVersion {
+        &self.runtime
+    }
+
+    /// The content-hashed bundle URL under the configured base path.
+    pub fn src(&self) -> String {
+        let base = self.base_path.as_deref().unwrap_or("").trim_end_matches('/');
+        format!("{base}{}", silcrow_js_hashed_path())
+    }
 }
 
-/// Returns a raw HTML `<script>` tag pointing to the Silcrow JS bundle.
-pub fn script_tag() -> &'static str {
-    "<script src=\"/_silcrow/silcrow.js\" defer></script>"
+impl fmt::Display for ScriptTag {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "<script src=\"{}\"", escape_attr(&self.src()))?;
+        if self.integrity {
+            write!(f, " integrity=\"{SILCROW_JS_INTEGRITY}\" crossorigin=\"anonymous\"")?;
+        }
+        write!(f, " data-runtime=\"{}\"", escape_attr(&self.runtime.token()))?;
+        if let Some(nonce) = &self.nonce {
+            write!(f, " nonce=\"{}\"", escape_attr(nonce))?;
+        }
+        if let Some(timeout) = self.timeout {
+            write!(f, " data-timeout=\"{}\"", timeout.as_millis())?;
+        }
+        if let Some(ttl) = self.cache_ttl {
+            write!(f, " data-cache-ttl=\"{}\"", ttl.as_millis())?;
+        }
+        if let Some(entries) = self.max_cache {
+            write!(f, " data-max-cache=\"{entries}\"")?;
+        }
+        f.write_str(" defer></script>")
+    }
 }
@@ -0,0 +1,383 @@
+// ./crates/pilcrow/src/cache.rs
+
+use std::collections::HashMap;
+use std::future::Future;
+use std::pin::Pin;
+use std::sync::{Arc, Mutex};
+use std::task::{Context, Poll};
+use std::time::{Duration, Instant};
+
+use axum::async_trait;
+use axum::body::{Body, HttpBody};
+use axum::extract::FromRequestParts;
+use axum::http::{header, request::Parts, HeaderValue, Method, Request, StatusCode};
+use axum::response::{IntoResponse, Response};
+use tower_layer::Layer;
+use tower_service::Service;
+
+use crate::extract::{RequestMode, SilcrowRequest};
+use crate::idempotency::StoredResponse;
+use crate::select::AppError;
+
+/// Set to `hit` on responses served from the cache.
+pub const CACHE_STATUS_HEADER: &str = "silcrow-server-cache";
+
+/// Responses larger than this, or of unknown length, are passed through but never stored.
+const MAX_CACHED_BYTES: usize = 4 * 1024 * 1024;
+
+// ════════════════════════════════════════════════════════════
+// 1. Per-Response Policy
+// ════════════════════════════════════════════════════════════
+
+/// How long a response may be cached and which tags invalidate it; set via
+/// [`ResponseExt::cache_for`](crate::ResponseExt::cache_for) and
+/// [`ResponseExt::cache_tag`](crate::ResponseExt::cache_tag).
+#[derive(Debug, Clone, Default)]
+pub struct CachePolicy {
+    /// Overrides the layer's default TTL.
+    pub ttl: Option<Duration>,
+    pub tags: Vec<String>,
+}
+
+// ════════════════════════════════════════════════════════════
+// 2. The Store
+// ════════════════════════════════════════════════════════════
+
+/// Backing storage for [`CacheLayer`]; keys already include the negotiated
+/// representation, so stores never need to look inside requests.
+///
+/// A hit must carry the [`extensions`](StoredResponse::extensions) it was
+/// stored with: a [`LayoutLayer`](crate::LayoutLayer) further out reads them to
+/// wrap the page. Stores that serialize responses lose them, so keep a
+/// `CacheLayer` backed by one outside every `LayoutLayer`.
+#[async_trait]
+pub trait CacheStore: Send + Sync + 'static {
+    async fn get(&self, key: &str) -> Option<StoredResponse>;
+    async fn put(&self, key: &str, response: StoredResponse, ttl: Duration, tags: &[String]);
+    /// Drops every entry stored with `tag`.
+    async fn invalidate_tag(&self, tag: &str);
+    async fn clear(&self);
+}
+
+struct CacheEntry {
+    response: StoredResponse,
+    expires: Instant,
+    tags: Vec<String>,
+}
+
+/// An in-process store holding at most `capacity` entries; the default.
+pub struct MemoryCache {
+    entries: Mutex<HashMap<String, CacheEntry>>,
+    capacity: usize,
+}
+
+impl MemoryCache {
+    pub fn new(capacity: usize) -> Self {
+        MemoryCache {
+            entries: Mutex::new(HashMap::new()),
+            capacity,
+        }
+    }
+}
+
+impl Default for MemoryCache {
+    fn default() -> Self {
+        MemoryCache::new(1024)
+    }
+}
+
+#[async_trait]
+impl CacheStore for MemoryCache {
+    async fn get(&self, key: &str) -> Option<StoredResponse> {
+        let mut entries = self.entries.lock().unwrap_or_else(|e| e.into_inner());
+        match entries.get(key) {
+            Some(entry) if entry.expires > Instant::now() => Some(entry.response.clone()),
+            Some(_) => {
+                entries.remove(key);
+                None
+            }
+            None => None,
+        }
+    }
+
+    async fn put(&self, key: &str, response: StoredResponse, ttl: Duration, tags: &[String]) {
+        let mut entries = self.entries.lock().unwrap_or_else(|e| e.into_inner());
+        if entries.len() >= self.capacity && !entries.contains_key(key) {
+            let now = Instant::now();
+            entries.retain(|_, entry| entry.expires > now);
+            // Still full: evict whatever would have expired first
+            if entries.len() >= self.capacity {
+                let oldest = entries
+                    .iter()
+                    .min_by_key(|(_, entry)| entry.expires)
+                    .map(|(key, _)| key.clone());
+                if let Some(oldest) = oldest {
+                    entries.remove(&oldest);
+                }
+            }
+        }
+        entries.insert(
+            key.to_string(),
+            CacheEntry {
+                response,
+                expires: Instant::now() + ttl,
+                tags: tags.to_vec(),
+            },
+        );
+    }
+
+    async fn invalidate_tag(&self, tag: &str) {
+        let mut entries = self.entries.lock().unwrap_or_else(|e| e.into_inner());
+        entries.retain(|_, entry| !entry.tags.iter().any(|t| t == tag));
+    }
+
+    async fn clear(&self) {
+        let mut entries = self.entries.lock().unwrap_or_else(|e| e.into_inner());
+        entries.clear();
+    }
+}
+
+// ════════════════════════════════════════════════════════════
+// 3. The Handler-Side Handle
+// ════════════════════════════════════════════════════════════
+
+/// Invalidates cached responses from handlers (as an extractor) or from
+/// anywhere else via [`CacheLayer::handle`].
+#[derive(Clone)]
+pub struct ResponseCache {
+    store: Arc<dyn CacheStore>,
+}
+
+impl ResponseCache {
+    pub async fn invalidate_tag(&self, tag: &str) {
+        self.store.invalidate_tag(tag).await;
+    }
+
+    pub async fn clear(&self) {
+        self.store.clear().await;
+    }
+}
+
+#[async_trait]
+impl<S> FromRequestParts<S> for ResponseCache
+where
+    S: Send + Sync,
+{
+    type Rejection = AppError;
+
+    async fn from_request_parts(parts: &mut Parts, _state: &S) -> Result<Self, Self::Rejection> {
+        parts.extensions.get::<ResponseCache>().cloned().ok_or_else(|| {
+            AppError::Internal(anyhow::anyhow!(
+                "ResponseCache extractor used without CacheLayer"
+            ))
+        })
+    }
+}
+
+// ════════════════════════════════════════════════════════════
+// 4. The Middleware
+// ════════════════════════════════════════════════════════════
+
+/// Caches rendered `GET`/`HEAD` responses on the server.
+///
+/// Caching is opt-in: only responses marked with
+/// [`cache_for`](crate::ResponseExt::cache_for) or
+/// [`cache_tag`](crate::ResponseExt::cache_tag) are stored. Entries are keyed by method, path, query, the negotiated [`RequestMode`],
+/// whether silcrow.js sent the request, its target selector and `Accept`,
+/// so a JSON payload is never served to a browser or a fragment to a hard load.
+/// Of those, only `200` responses of known length without `Set-Cookie`,
+/// `silcrow-cache: no-cache` or a `private`/`no-store` `Cache-Control` are
+/// stored, so streamed bodies pass straight through; requests asking for a
+/// JSON patch bypass the cache.
+///
+/// Everything under the layer is shared between users: don't wrap routes
+/// that render per-session content (CSRF tokens included).
+#[derive(Clone)]
+pub struct CacheLayer {
+    store: Arc<dyn CacheStore>,
+    ttl: Duration,
+}
+
+impl Default for CacheLayer {
+    fn default() -> Self {
+        CacheLayer {
+            store: Arc::new(MemoryCache::default()),
+            ttl: Duration::from_secs(60),
+        }
+    }
+}
+
+impl CacheLayer {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn store(mut self, store: impl CacheStore) -> Self {
+        self.store = Arc::new(store);
+        self
+    }
+
+    /// TTL for responses tagged via [`cache_tag`](crate::ResponseExt::cache_tag)
+    /// without a [`cache_for`](crate::ResponseExt::cache_for). Defaults to one minute.
+    pub fn ttl(mut self, ttl: Duration) -> Self {
+        self.ttl = ttl;
+        self
+    }
+
+    /// A handle for invalidating entries outside of request handlers.
+    pub fn handle(&self) -> ResponseCache {
+        ResponseCache {
+            store: self.store.clone(),
+        }
+    }
+}
+
+impl<S> Layer<S> for CacheLayer {
+    type Service = CacheService<S>;
+
+    fn layer(&self, inner: S) -> Self::Service {
+        CacheService {
+            inner,
+            store: self.store.clone(),
+            ttl: self.ttl,
+        }
+    }
+}
+
+#[derive(Clone)]
+pub struct CacheService<S> {
+    inner: S,
+    store: Arc<dyn CacheStore>,
+    ttl: Duration,
+}
+
+impl<S> Service<Request<Body>> for CacheService<S>
+where
+    S: Service<Request<Body>, Response = Response> + Clone + Send + 'static,
+    S::Future: Send + 'static,
+{
+    type Response = Response;
+    type Error = S::Error;
+    type Future = Pin<Box<dyn Future<Output = Result<Response, S::Error>> + Send>>;
+
+    fn poll_ready(&mut self, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        self.inner.poll_ready(cx)
+    }
+
+    fn call(&mut self, request: Request<Body>) -> Self::Future {
+        let store = self.store.clone();
+        let default_ttl = self.ttl;
+        let clone = self.inner.clone();
+        let mut inner = std::mem::replace(&mut self.inner, clone);
+
+        Box::pin(async move {
+            let mut request = request;
+            request.extensions_mut().insert(ResponseCache {
+                store: store.clone(),
+            });
+
+            let cacheable = matches!(*request.method(), Method::GET | Method::HEAD)
+                && !request.headers().contains_key("silcrow-patch-base");
+            if !cacheable {
+                return inner.call(request).await;
+            }
+
+            let key = cache_key(&request);
+            if let Some(stored) = store.get(&key).await {
+                return Ok(hit(stored));
+            }
+
+            let response = inner.call(request).await?;
+            let Some(policy) = storable(&response) else {
+                return Ok(response);
+            };
+
+            let (parts, body) = response.into_parts();
+            let bytes = match axum::body::to_bytes(body, MAX_CACHED_BYTES).await {
+                Ok(bytes) => bytes,
+                Err(err) => {
+                    let err = anyhow::anyhow!("Failed to buffer response body: {err}");
+                    return Ok(AppError::Internal(err).into_response());
+                }
+            };
+            let stored = StoredResponse {
+                status: parts.status,
+                headers: parts.headers.clone(),
+                body: bytes.clone(),
+                extensions: parts.extensions.clone(),
+            };
+            let ttl = policy.ttl.unwrap_or(default_ttl);
+            store.put(&key, stored, ttl, &policy.tags).await;
+            Ok(Response::from_parts(parts, Body::from(bytes)))
+        })
+    }
+}
+
+fn cache_key(request: &Request<Body>) -> String {
+    let silcrow = SilcrowRequest::from_headers(request.headers());
+    // Honor a mode another layer already settled on
+    let mode = request
+        .extensions()
+        .get::<RequestMode>()
+        .copied()
+        .unwrap_or_else(|| silcrow.preferred_mode());
+    let mode = match mode {
+        RequestMode::Html => "html",
+        RequestMode::Json => "json",
+        RequestMode::Navigate => "navigate",
+        RequestMode::Other(media) => media,
+    };
+    let origin = if silcrow.is_silcrow { "silcrow" } else { "browser" };
+
+    // Handlers may offer media types beyond the mode, so `Accept` is part of the key
+    format!(
+        "{} {}?{} {mode} {origin} {} {}",
+        request.method(),
+        request.uri().path(),
+        request.uri().query().unwrap_or(""),
+        silcrow.target.as_deref().unwrap_or(""),
+        silcrow.accept
+    )
+}
+
+/// The policy to store `response` under, or `None` if it must not be cached.
+fn storable(response: &Response) -> Option<CachePolicy> {
+    let policy = response.extensions().get::<CachePolicy>()?;
+    if response.status() != StatusCode::OK || response.headers().contains_key(header::SET_COOKIE) {
+        return None;
+    }
+    // Buffering is bounded up front; streamed bodies have no exact size
+    let size = response.body().size_hint().exact();
+    if size.is_none_or(|size| size > MAX_CACHED_BYTES as u64) {
+        return None;
+    }
+    let headers = response.headers();
+    if headers
+        .get("silcrow-cache")
+        .is_some_and(|v| v.as_bytes() == b"no-cache")
+    {
+        return None;
+    }
+    let private = headers
+        .get_all(header::CACHE_CONTROL)
+        .iter()
+        .filter_map(|v| v.to_str().ok())
+        .flat_map(|v| v.split(','))
+        .any(|d| matches!(d.trim().to_ascii_lowercase().as_str(), "private" | "no-store"));
+    if private {
+        return None;
+    }
+    Some(policy.clone())
+}
+
+fn hit(stored: StoredResponse) -> Response {
+    let mut response = Response::new(Body::from(stored.body));
+    *response.status_mut() = stored.status;
+    *response.headers_mut() = stored.headers;
+    // Restores the layout markers, so a hit is wrapped like the original
+    *response.extensions_mut() = stored.extensions;
+    response
+        .headers_mut()
+        .insert(CACHE_STATUS_HEADER, HeaderValue::from_static("hit"));
+    response
+}
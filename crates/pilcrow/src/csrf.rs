@@ -0,0 +1,289 @@
+// ./crates/pilcrow/src/csrf.rs
+
+use std::future::Future;
+use std::pin::Pin;
+use std::task::{Context, Poll};
+
+use axum::async_trait;
+use axum::body::{Body, Bytes};
+use axum::extract::FromRequestParts;
+use axum::http::{header, request::Parts, HeaderMap, HeaderValue, Method, Request, StatusCode};
+use axum::response::{IntoResponse, Response};
+use cookie::{Cookie, SameSite};
+use futures_util::stream::{self, StreamExt};
+use tower_layer::Layer;
+use tower_service::Service;
+
+use crate::extract::SilcrowRequest;
+use crate::select::{AppError, Rejection};
+
+/// Cookie holding the token; readable by silcrow.js for the double submit.
+pub const CSRF_COOKIE: &str = "silcrow_csrf";
+/// Header silcrow.js sends the token in on every non-GET navigation.
+pub const CSRF_HEADER: &str = "silcrow-csrf";
+/// Form field for submissions that can't set headers.
+pub const CSRF_FIELD: &str = "_csrf";
+
+/// Largest urlencoded body, or multipart prefix, scanned for the form field.
+const MAX_FORM_BYTES: usize = 1024 * 1024;
+
+// ════════════════════════════════════════════════════════════
+// 1. The Token
+// ════════════════════════════════════════════════════════════
+
+/// The current request's CSRF token, set by [`CsrfLayer`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CsrfToken(String);
+
+impl CsrfToken {
+    fn generate() -> Result<Self, getrandom::Error> {
+        let mut bytes = [0u8; 32];
+        getrandom::getrandom(&mut bytes)?;
+        Ok(CsrfToken(bytes.iter().map(|b| format!("{b:02x}")).collect()))
+    }
+
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
+
+    /// The hidden `<input>` to place inside plain HTML forms.
+    pub fn field(&self) -> String {
+        format!("<input type=\"hidden\" name=\"{CSRF_FIELD}\" value=\"{}\">", self.0)
+    }
+
+    fn matches(&self, candidate: &str) -> bool {
+        // Constant time, so the comparison doesn't leak a matching prefix
+        let (a, b) = (self.0.as_bytes(), candidate.as_bytes());
+        a.len() == b.len() && a.iter().zip(b).fold(0u8, |acc, (x, y)| acc | (x ^ y)) == 0
+    }
+}
+
+#[async_trait]
+impl<S> FromRequestParts<S> for CsrfToken
+where
+    S: Send + Sync,
+{
+    type Rejection = AppError;
+
+    async fn from_request_parts(parts: &mut Parts, _state: &S) -> Result<Self, Self::Rejection> {
+        parts
+            .extensions
+            .get::<CsrfToken>()
+            .cloned()
+            .ok_or_else(|| AppError::Internal(anyhow::anyhow!("CsrfLayer is not installed")))
+    }
+}
+
+#[cfg(feature = "maud")]
+impl ::maud::Render for CsrfToken {
+    fn render(&self) -> ::maud::Markup {
+        ::maud::PreEscaped(self.field())
+    }
+}
+
+// ════════════════════════════════════════════════════════════
+// 2. Middleware
+// ════════════════════════════════════════════════════════════
+
+/// Double-submit CSRF protection.
+///
+/// Every visitor gets a random token in the `silcrow_csrf` cookie. Unsafe
+/// requests (anything but GET, HEAD, OPTIONS and TRACE) must repeat it in
+/// the `silcrow-csrf` header, which silcrow.js does automatically, or in
+/// the `_csrf` field of a form. Multipart forms must carry the field within
+/// their first MiB, so put [`CsrfToken::field`] before any file inputs.
+/// Failures are rejected with a negotiated 403 [`Rejection`].
+#[derive(Debug, Clone, Default)]
+pub struct CsrfLayer {
+    secure: bool,
+}
+
+impl CsrfLayer {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Marks the cookie `Secure`; enable whenever the app is served over HTTPS.
+    pub fn secure(mut self, secure: bool) -> Self {
+        self.secure = secure;
+        self
+    }
+}
+
+impl<S> Layer<S> for CsrfLayer {
+    type Service = CsrfService<S>;
+
+    fn layer(&self, inner: S) -> Self::Service {
+        CsrfService {
+            inner,
+            secure: self.secure,
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct CsrfService<S> {
+    inner: S,
+    secure: bool,
+}
+
+impl<S> Service<Request<Body>> for CsrfService<S>
+where
+    S: Service<Request<Body>, Response = Response> + Clone + Send + 'static,
+    S::Future: Send + 'static,
+{
+    type Response = Response;
+    type Error = S::Error;
+    type Future = Pin<Box<dyn Future<Output = Result<Response, S::Error>> + Send>>;
+
+    fn poll_ready(&mut self, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        self.inner.poll_ready(cx)
+    }
+
+    fn call(&mut self, request: Request<Body>) -> Self::Future {
+        let secure = self.secure;
+        let clone = self.inner.clone();
+        let mut inner = std::mem::replace(&mut self.inner, clone);
+
+        Box::pin(async move {
+            let existing = cookie_token(request.headers());
+            let is_safe = matches!(
+                *request.method(),
+                Method::GET | Method::HEAD | Method::OPTIONS | Method::TRACE
+            );
+
+            let mut request = request;
+            if !is_safe {
+                let silcrow = SilcrowRequest::from_headers(request.headers());
+                let forbidden = |message: &str| {
+                    Rejection::new(StatusCode::FORBIDDEN, message, &silcrow).into_response()
+                };
+                let Some(token) = &existing else {
+                    return Ok(forbidden("Missing CSRF cookie"));
+                };
+                match verify(request, token).await {
+                    Ok(verified) => request = verified,
+                    Err(message) => return Ok(forbidden(message)),
+                }
+            }
+
+            let (token, issued) = match existing {
+                Some(token) => (token, false),
+                None => match CsrfToken::generate() {
+                    Ok(token) => (token, true),
+                    Err(err) => {
+                        let err = anyhow::anyhow!("CSRF token generation failed: {err}");
+                        return Ok(AppError::Internal(err).into_response());
+                    }
+                },
+            };
+
+            request.extensions_mut().insert(token.clone());
+            let mut response = inner.call(request).await?;
+
+            if issued {
+                let cookie = Cookie::build((CSRF_COOKIE, token.0))
+                    .path("/")
+                    .same_site(SameSite::Lax)
+                    .secure(secure)
+                    .build();
+                if let Ok(value) = HeaderValue::from_str(&cookie.to_string()) {
+                    response.headers_mut().append(header::SET_COOKIE, value);
+                }
+            }
+            Ok(response)
+        })
+    }
+}
+
+fn cookie_token(headers: &HeaderMap) -> Option<CsrfToken> {
+    headers
+        .get_all(header::COOKIE)
+        .iter()
+        .filter_map(|v| v.to_str().ok())
+        .flat_map(|v| Cookie::split_parse(v).flatten())
+        .find(|c| c.name() == CSRF_COOKIE)
+        // Anything but a token we could have issued is treated as absent
+        .filter(|c| c.value().len() == 64 && c.value().bytes().all(|b| b.is_ascii_hexdigit()))
+        .map(|c| CsrfToken(c.value().to_string()))
+}
+
+/// Checks the header, falling back to the `_csrf` form field. The body is
+/// buffered (urlencoded) or peeked at (multipart) for the fallback and
+/// handed back intact; the error is the rejection message.
+async fn verify(request: Request<Body>, token: &CsrfToken) -> Result<Request<Body>, &'static str> {
+    if let Some(header) = request.headers().get(CSRF_HEADER) {
+        return match header.to_str() {
+            Ok(value) if token.matches(value) => Ok(request),
+            _ => Err("Invalid CSRF token"),
+        };
+    }
+
+    let content_type = request
+        .headers()
+        .get(header::CONTENT_TYPE)
+        .and_then(|v| v.to_str().ok())
+        .unwrap_or("")
+        .to_string();
+
+    if content_type.starts_with("application/x-www-form-urlencoded") {
+        let (parts, body) = request.into_parts();
+        let bytes = axum::body::to_bytes(body, MAX_FORM_BYTES)
+            .await
+            .map_err(|_| "Form too large to verify CSRF token")?;
+
+        return match form_urlencoded_field(&bytes, CSRF_FIELD) {
+            Some(value) if token.matches(&value) => Ok(Request::from_parts(parts, Body::from(bytes))),
+            _ => Err("Invalid CSRF token"),
+        };
+    }
+
+    if let Ok(boundary) = multer::parse_boundary(&content_type) {
+        let (parts, body) = request.into_parts();
+        let mut rest = body.into_data_stream();
+        let mut head: Vec<Bytes> = Vec::new();
+        let mut size = 0;
+        while size < MAX_FORM_BYTES {
+            match rest.next().await {
+                Some(Ok(chunk)) => {
+                    size += chunk.len();
+                    head.push(chunk);
+                }
+                Some(Err(_)) => return Err("Invalid CSRF token"),
+                None => break,
+            }
+        }
+
+        return match multipart_field(&head, boundary, CSRF_FIELD).await {
+            Some(value) if token.matches(&value) => {
+                let head = stream::iter(head.into_iter().map(Ok::<_, axum::Error>));
+                Ok(Request::from_parts(parts, Body::from_stream(head.chain(rest))))
+            }
+            _ => Err("Invalid CSRF token"),
+        };
+    }
+
+    Err("Missing CSRF token")
+}
+
+/// Finds a text field among the parts that fit in `head`, the start of a
+/// multipart body; a truncated part just ends the search.
+async fn multipart_field(head: &[Bytes], boundary: String, name: &str) -> Option<String> {
+    let chunks = stream::iter(head.iter().cloned().map(Ok::<_, std::io::Error>));
+    let mut multipart = multer::Multipart::new(chunks, boundary);
+    while let Ok(Some(field)) = multipart.next_field().await {
+        if field.name() == Some(name) {
+            return field.text().await.ok();
+        }
+    }
+    None
+}
+
+fn form_urlencoded_field(body: &[u8], name: &str) -> Option<String> {
+    let body = std::str::from_utf8(body).ok()?;
+    body.split('&').find_map(|pair| {
+        let (key, value) = pair.split_once('=').unwrap_or((pair, ""));
+        let decode = |s: &str| urlencoding::decode(&s.replace('+', " ")).ok().map(|v| v.into_owned());
+        (decode(key)? == name).then(|| decode(value)).flatten()
+    })
+}
@@ -0,0 +1,82 @@
+// ./crates/pilcrow/src/diff.rs
+
+use serde_json::{Map, Value};
+
+// ════════════════════════════════════════════════════════════
+// 1. RFC 7386 Merge Patches
+// ════════════════════════════════════════════════════════════
+
+/// Computes the RFC 7386 merge patch that turns `prev` into `next`.
+///
+/// Unchanged keys are omitted, removed keys become `null`, and anything that
+/// is not an object on both sides (arrays included) is replaced wholesale —
+/// which is exactly what the runtime's `s-list` reconciliation expects.
+pub fn merge_patch(prev: &Value, next: &Value) -> Value {
+    match (prev, next) {
+        (Value::Object(prev), Value::Object(next)) => {
+            let mut patch = Map::new();
+
+            for (key, next_val) in next {
+                match prev.get(key) {
+                    Some(prev_val) if prev_val == next_val => {}
+                    Some(prev_val @ Value::Object(_)) if next_val.is_object() => {
+                        patch.insert(key.clone(), merge_patch(prev_val, next_val));
+                    }
+                    _ => {
+                        patch.insert(key.clone(), next_val.clone());
+                    }
+                }
+            }
+
+            for key in prev.keys() {
+                if !next.contains_key(key) {
+                    patch.insert(key.clone(), Value::Null);
+                }
+            }
+
+            Value::Object(patch)
+        }
+        _ => next.clone(),
+    }
+}
+
+/// Applies an RFC 7386 merge patch to `target` in place.
+pub fn apply_merge_patch(target: &mut Value, patch: &Value) {
+    let Value::Object(patch) = patch else {
+        *target = patch.clone();
+        return;
+    };
+
+    if !target.is_object() {
+        *target = Value::Object(Map::new());
+    }
+
+    if let Value::Object(map) = target {
+        for (key, value) in patch {
+            if value.is_null() {
+                map.remove(key);
+            } else {
+                apply_merge_patch(map.entry(key.clone()).or_insert(Value::Null), value);
+            }
+        }
+    }
+}
+
+// ════════════════════════════════════════════════════════════
+// 2. Payload Versions
+// ════════════════════════════════════════════════════════════
+
+/// A short, stable fingerprint of a JSON payload (FNV-1a over its canonical
+/// serialization). Sent as `silcrow-version` so the runtime can tell the
+/// server which snapshot its bindings currently reflect.
+pub fn version(value: &Value) -> String {
+    const OFFSET: u64 = 0xcbf2_9ce4_8422_2325;
+    const PRIME: u64 = 0x0000_0100_0000_01b3;
+
+    let canonical = value.to_string();
+    let hash = canonical
+        .bytes()
+        .fold(OFFSET, |hash, byte| (hash ^ byte as u64).wrapping_mul(PRIME));
+
+    format!("{hash:016x}")
+}
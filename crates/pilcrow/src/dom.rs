@@ -0,0 +1,451 @@
+// ./crates/pilcrow/src/dom.rs
+
+//! A deliberately small HTML tree used to inspect and rewrite Silcrow
+//! markup on the server. It targets well-formed output from template
+//! engines (Maud, Askama, ...): it understands void and raw-text elements,
+//! comments and doctypes, but does not implement the HTML5 implied-end-tag
+//! rules, so hand-written markup should close its own `<p>`/`<li>` tags.
+
+// ════════════════════════════════════════════════════════════
+// 1. The Node Tree
+// ════════════════════════════════════════════════════════════
+
+#[derive(Debug, Clone)]
+pub(crate) enum Node {
+    Element(Element),
+    /// Text kept exactly as written (entities are not decoded).
+    Text(String),
+    Comment(String),
+    Doctype(String),
+}
+
+#[derive(Debug, Clone)]
+pub(crate) struct Element {
+    pub name: String,
+    /// Attribute values are entity-decoded; `None` is a bare attribute.
+    pub attrs: Vec<(String, Option<String>)>,
+    pub children: Vec<Node>,
+}
+
+const VOID_ELEMENTS: &[&str] = &[
+    "area", "base", "br", "col", "embed", "hr", "img", "input", "link", "meta", "source",
+    "track", "wbr",
+];
+
+const RAW_TEXT_ELEMENTS: &[&str] = &["script", "style", "textarea", "title"];
+
+impl Element {
+    pub fn new(name: &str) -> Self {
+        Element {
+            name: name.to_ascii_lowercase(),
+            attrs: Vec::new(),
+            children: Vec::new(),
+        }
+    }
+
+    pub fn attr(&self, name: &str) -> Option<&str> {
+        self.attrs
+            .iter()
+            .find(|(n, _)| n == name)
+            .map(|(_, v)| v.as_deref().unwrap_or(""))
+    }
+
+    pub fn has_attr(&self, name: &str) -> bool {
+        self.attrs.iter().any(|(n, _)| n == name)
+    }
+
+    pub fn set_attr(&mut self, name: &str, value: Option<String>) {
+        match self.attrs.iter_mut().find(|(n, _)| n == name) {
+            Some(slot) => slot.1 = value,
+            None => self.attrs.push((name.to_string(), value)),
+        }
+    }
+
+    pub fn remove_attr(&mut self, name: &str) {
+        self.attrs.retain(|(n, _)| n != name);
+    }
+
+    /// Replaces all children with a single escaped text node.
+    pub fn set_text(&mut self, text: &str) {
+        self.children = if text.is_empty() {
+            Vec::new()
+        } else {
+            vec![Node::Text(escape_text(text))]
+        };
+    }
+
+    /// Element children only, mirroring `Element.children` in the DOM.
+    pub fn element_children(&self) -> impl Iterator<Item = &Element> {
+        self.children.iter().filter_map(Node::as_element)
+    }
+
+    fn is_void(&self) -> bool {
+        VOID_ELEMENTS.contains(&self.name.as_str())
+    }
+}
+
+impl Node {
+    pub fn as_element(&self) -> Option<&Element> {
+        match self {
+            Node::Element(el) => Some(el),
+            _ => None,
+        }
+    }
+
+    pub fn as_element_mut(&mut self) -> Option<&mut Element> {
+        match self {
+            Node::Element(el) => Some(el),
+            _ => None,
+        }
+    }
+}
+
+// ════════════════════════════════════════════════════════════
+// 2. Traversal
+// ════════════════════════════════════════════════════════════
+
+/// Visits every element depth-first. `template` contents are skipped,
+/// matching `querySelectorAll` on a live document.
+pub(crate) fn walk<'a>(nodes: &'a [Node], f: &mut impl FnMut(&'a Element)) {
+    for node in nodes {
+        if let Node::Element(el) = node {
+            f(el);
+            if el.name != "template" {
+                walk(&el.children, f);
+            }
+        }
+    }
+}
+
+/// Mutable counterpart of [`walk`], also skipping `template` contents.
+pub(crate) fn walk_mut(nodes: &mut [Node], f: &mut impl FnMut(&mut Element)) {
+    for node in nodes {
+        if let Node::Element(el) = node {
+            f(el);
+            if el.name != "template" {
+                walk_mut(&mut el.children, f);
+            }
+        }
+    }
+}
+
+/// Visits an element and all of its descendants, including the element itself.
+pub(crate) fn walk_subtree_mut(el: &mut Element, f: &mut impl FnMut(&mut Element)) {
+    f(el);
+    walk_mut(&mut el.children, f);
+}
+
+// ════════════════════════════════════════════════════════════
+// 3. Parsing
+// ════════════════════════════════════════════════════════════
+
+pub(crate) fn parse(html: &str) -> Vec<Node> {
+    let mut parser = Parser {
+        src: html,
+        pos: 0,
+        stack: vec![Element::new("#root")],
+    };
+    parser.run();
+
+    while parser.stack.len() > 1 {
+        parser.close_top();
+    }
+    parser.stack.pop().map(|root| root.children).unwrap_or_default()
+}
+
+struct Parser<'a> {
+    src: &'a str,
+    pos: usize,
+    stack: Vec<Element>,
+}
+
+impl<'a> Parser<'a> {
+    fn rest(&self) -> &'a str {
+        &self.src[self.pos..]
+    }
+
+    fn push_node(&mut self, node: Node) {
+        if let Some(top) = self.stack.last_mut() {
+            top.children.push(node);
+        }
+    }
+
+    fn close_top(&mut self) {
+        if let Some(el) = self.stack.pop() {
+            self.push_node(Node::Element(el));
+        }
+    }
+
+    fn run(&mut self) {
+        while self.pos < self.src.len() {
+            let rest = self.rest();
+
+            if let Some(comment) = rest.strip_prefix("<!--") {
+                let end = comment.find("-->").unwrap_or(comment.len());
+                self.push_node(Node::Comment(comment[..end].to_string()));
+                self.pos += 4 + (end + 3).min(comment.len());
+            } else if rest.starts_with("<!") {
+                let end = rest.find('>').unwrap_or(rest.len());
+                self.push_node(Node::Doctype(rest[2..end].to_string()));
+                self.pos += (end + 1).min(rest.len());
+            } else if rest.starts_with("</") && starts_tag_name(&rest[2..]) {
+                self.parse_end_tag();
+            } else if rest.starts_with('<') && starts_tag_name(&rest[1..]) {
+                self.parse_start_tag();
+            } else {
+                // Text runs until the next thing that looks like markup
+                let first = rest.chars().next().map_or(1, char::len_utf8);
+                let end = rest[first..]
+                    .find('<')
+                    .map(|i| i + first)
+                    .unwrap_or(rest.len());
+                self.push_text(&rest[..end]);
+                self.pos += end;
+            }
+        }
+    }
+
+    fn push_text(&mut self, text: &str) {
+        if let Some(top) = self.stack.last_mut() {
+            if let Some(Node::Text(prev)) = top.children.last_mut() {
+                prev.push_str(text);
+                return;
+            }
+            top.children.push(Node::Text(text.to_string()));
+        }
+    }
+
+    fn parse_end_tag(&mut self) {
+        let rest = &self.rest()[2..];
+        let end = rest.find('>').unwrap_or(rest.len());
+        let name = rest[..end]
+            .split(|c: char| c.is_whitespace() || c == '/')
+            .next()
+            .unwrap_or("")
+            .to_ascii_lowercase();
+        self.pos += 2 + (end + 1).min(rest.len());
+
+        // Stray end tags are ignored; otherwise close everything up to the match
+        if let Some(idx) = self.stack.iter().rposition(|el| el.name == name) {
+            if idx > 0 {
+                while self.stack.len() > idx {
+                    self.close_top();
+                }
+            }
+        }
+    }
+
+    fn parse_start_tag(&mut self) {
+        let bytes = self.src.as_bytes();
+        let mut i = self.pos + 1;
+        let name_start = i;
+        while i < bytes.len() && !bytes[i].is_ascii_whitespace() && bytes[i] != b'>' && bytes[i] != b'/' {
+            i += 1;
+        }
+        let mut el = Element::new(&self.src[name_start..i]);
+        let mut self_closing = false;
+
+        loop {
+            while i < bytes.len() && bytes[i].is_ascii_whitespace() {
+                i += 1;
+            }
+            if i >= bytes.len() {
+                break;
+            }
+            match bytes[i] {
+                b'>' => {
+                    i += 1;
+                    break;
+                }
+                b'/' => {
+                    self_closing = true;
+                    i += 1;
+                    continue;
+                }
+                _ => {}
+            }
+            self_closing = false;
+
+            let attr_start = i;
+            while i < bytes.len()
+                && !bytes[i].is_ascii_whitespace()
+                && !matches!(bytes[i], b'=' | b'>' | b'/')
+            {
+                i += 1;
+            }
+            let attr_name = self.src[attr_start..i].to_ascii_lowercase();
+            if attr_name.is_empty() {
+                i += 1;
+                continue;
+            }
+
+            while i < bytes.len() && bytes[i].is_ascii_whitespace() {
+                i += 1;
+            }
+            if i < bytes.len() && bytes[i] == b'=' {
+                i += 1;
+                while i < bytes.len() && bytes[i].is_ascii_whitespace() {
+                    i += 1;
+                }
+                let value = if i < bytes.len() && (bytes[i] == b'"' || bytes[i] == b'\'') {
+                    let quote = bytes[i];
+                    let start = i + 1;
+                    let end = self.src[start..]
+                        .bytes()
+                        .position(|b| b == quote)
+                        .map(|p| start + p)
+                        .unwrap_or(bytes.len());
+                    i = (end + 1).min(bytes.len());
+                    &self.src[start..end]
+                } else {
+                    let start = i;
+                    while i < bytes.len() && !bytes[i].is_ascii_whitespace() && bytes[i] != b'>' {
+                        i += 1;
+                    }
+                    &self.src[start..i]
+                };
+                if !el.has_attr(&attr_name) {
+                    el.attrs.push((attr_name, Some(decode_entities(value))));
+                }
+            } else if !el.has_attr(&attr_name) {
+                el.attrs.push((attr_name, None));
+            }
+        }
+        self.pos = i;
+
+        if el.is_void() || self_closing {
+            self.push_node(Node::Element(el));
+            return;
+        }
+
+        if RAW_TEXT_ELEMENTS.contains(&el.name.as_str()) {
+            let rest = self.rest();
+            let close = format!("</{}", el.name);
+            let end = rest
+                .to_ascii_lowercase()
+                .find(&close)
+                .unwrap_or(rest.len());
+            if end > 0 {
+                el.children.push(Node::Text(rest[..end].to_string()));
+            }
+            self.pos += end;
+            let after = self.rest();
+            if !after.is_empty() {
+                self.pos += after.find('>').map(|p| p + 1).unwrap_or(after.len());
+            }
+            self.push_node(Node::Element(el));
+            return;
+        }
+
+        self.stack.push(el);
+    }
+}
+
+fn starts_tag_name(s: &str) -> bool {
+    s.chars().next().is_some_and(|c| c.is_ascii_alphabetic())
+}
+
+fn decode_entities(value: &str) -> String {
+    if !value.contains('&') {
+        return value.to_string();
+    }
+
+    let mut out = String::with_capacity(value.len());
+    let mut rest = value;
+    while let Some(idx) = rest.find('&') {
+        out.push_str(&rest[..idx]);
+        rest = &rest[idx..];
+
+        let decoded = rest.find(';').and_then(|end| {
+            let entity = &rest[1..end];
+            let ch = match entity {
+                "amp" => Some('&'),
+                "lt" => Some('<'),
+                "gt" => Some('>'),
+                "quot" => Some('"'),
+                "apos" => Some('\''),
+                _ => entity
+                    .strip_prefix("#x")
+                    .or_else(|| entity.strip_prefix("#X"))
+                    .and_then(|hex| u32::from_str_radix(hex, 16).ok())
+                    .or_else(|| entity.strip_prefix('#').and_then(|d| d.parse().ok()))
+                    .and_then(char::from_u32),
+            };
+            ch.map(|c| (c, end))
+        });
+
+        match decoded {
+            Some((c, end)) => {
+                out.push(c);
+                rest = &rest[end + 1..];
+            }
+            None => {
+                out.push('&');
+                rest = &rest[1..];
+            }
+        }
+    }
+    out.push_str(rest);
+    out
+}
+
+// ════════════════════════════════════════════════════════════
+// 4. Serialization
+// ════════════════════════════════════════════════════════════
+
+pub(crate) fn escape_text(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+}
+
+fn escape_attr(value: &str) -> String {
+    value.replace('&', "&amp;").replace('"', "&quot;")
+}
+
+pub(crate) fn serialize(nodes: &[Node]) -> String {
+    let mut out = String::new();
+    for node in nodes {
+        write_node(node, &mut out);
+    }
+    out
+}
+
+fn write_node(node: &Node, out: &mut String) {
+    match node {
+        Node::Text(text) => out.push_str(text),
+        Node::Comment(text) => {
+            out.push_str("<!--");
+            out.push_str(text);
+            out.push_str("-->");
+        }
+        Node::Doctype(text) => {
+            out.push_str("<!");
+            out.push_str(text);
+            out.push('>');
+        }
+        Node::Element(el) => {
+            out.push('<');
+            out.push_str(&el.name);
+            for (name, value) in &el.attrs {
+                out.push(' ');
+                out.push_str(name);
+                if let Some(value) = value {
+                    out.push_str("=\"");
+                    out.push_str(&escape_attr(value));
+                    out.push('"');
+                }
+            }
+            out.push('>');
+            if el.is_void() {
+                return;
+            }
+            for child in &el.children {
+                write_node(child, out);
+            }
+            out.push_str("</");
+            out.push_str(&el.name);
+            out.push('>');
+        }
+    }
+}
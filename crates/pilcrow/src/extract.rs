@@ -3,17 +3,25 @@
 use axum::{
     async_trait,
     extract::FromRequestParts,
-    http::{request::Parts, StatusCode},
+    http::{request::Parts, HeaderMap, StatusCode},
 };
 
+use crate::diff;
+use crate::response::{json, JsonResponse};
+
 // ════════════════════════════════════════════════════════════
 // 1. The Unified Mode Enum
 // ════════════════════════════════════════════════════════════
-#[derive(Debug, PartialEq, Eq)]
+/// Non-exhaustive so new representations don't break downstream `match`es.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[non_exhaustive]
 pub enum RequestMode {
     Html,
     Json,
     Navigate,
+    /// A representation registered by the application, named by its media
+    /// type (`"text/csv"`); see [`SilcrowRequest::mode_among`].
+    Other(&'static str),
 }
 
 // ════════════════════════════════════════════════════════════
@@ -22,8 +30,16 @@ pub enum RequestMode {
 #[derive(Debug, Clone)]
 pub struct SilcrowRequest {
     pub is_silcrow: bool,
+    /// The `s-target` selector silcrow.js is swapping into, if any.
+    pub target: Option<String>,
     pub accepts_html: bool,
     pub accepts_json: bool,
+    /// The raw `Accept` header, for negotiating registered media types.
+    pub accept: String,
+    /// The `silcrow-version` of the JSON snapshot the runtime currently holds.
+    pub patch_base: Option<String>,
+    /// The runtime token silcrow.js was loaded with; see [`RuntimeVersion`](crate::version::RuntimeVersion).
+    pub runtime: Option<String>,
 }
 
 #[async_trait]
@@ -34,12 +50,26 @@ impl<S> FromRequestParts<S> for SilcrowRequest
     type Rejection = (StatusCode, &'static str);
 
     async fn from_request_parts(parts: &mut Parts, _state: &S) -> Result<Self, Self::Rejection> {
+        Ok(SilcrowRequest::from_headers(&parts.headers))
+    }
+}
+
+impl SilcrowRequest {
+    /// Reads the Silcrow request headers; used by middleware that can't run extractors.
+    pub fn from_headers(headers: &HeaderMap) -> Self {
         // Did silcrow.js send this request?
-        let is_silcrow = parts.headers.contains_key("silcrow-target");
+        let is_silcrow = headers.contains_key("silcrow-target");
+
+        // Full-page navigations send "true"; targeted ones the encoded selector
+        let target = headers
+            .get("silcrow-target")
+            .and_then(|v| v.to_str().ok())
+            .filter(|v| !v.is_empty() && *v != "true")
+            .and_then(|v| urlencoding::decode(v).ok())
+            .map(|v| v.into_owned());
 
         // What data format does the client want?
-        let accept = parts
-            .headers
+        let accept = headers
             .get(axum::http::header::ACCEPT)
             .and_then(|v| v.to_str().ok())
             .unwrap_or("");
@@ -47,11 +77,25 @@ async fn from_request_parts(parts: &mut Parts, _state: &S) -> Result<Self, Self:
         let accepts_html = accept.contains("text/html");
         let accepts_json = accept.contains("application/json");
 
-        Ok(SilcrowRequest {
+        let patch_base = headers
+            .get("silcrow-patch-base")
+            .and_then(|v| v.to_str().ok())
+            .map(str::to_owned);
+
+        let runtime = headers
+            .get("silcrow-runtime")
+            .and_then(|v| v.to_str().ok())
+            .map(str::to_owned);
+
+        SilcrowRequest {
             is_silcrow,
+            target,
             accepts_html,
             accepts_json,
-        })
+            accept: accept.to_string(),
+            patch_base,
+            runtime,
+        }
     }
 }
 
@@ -80,3 +124,95 @@ pub fn preferred_mode(&self) -> RequestMode {
         RequestMode::Json
     }
 }
+
+impl SilcrowRequest {
+    /// Like [`preferred_mode`](Self::preferred_mode), but also weighs the
+    /// media types in `custom`. One only wins when `Accept` ranks it above
+    /// HTML and JSON, so `*/*` clients and browsers keep their usual format.
+    pub fn mode_among(&self, custom: &[&'static str]) -> RequestMode {
+        if self.is_silcrow || custom.is_empty() {
+            return self.preferred_mode();
+        }
+        let mut offered = vec!["text/html", "application/json"];
+        offered.extend_from_slice(custom);
+        match self.negotiate_media(&offered) {
+            Some(media) if custom.contains(&media) => RequestMode::Other(media),
+            _ => self.preferred_mode(),
+        }
+    }
+
+    /// Picks the offered media type `Accept` ranks highest; ties go to the
+    /// more specific match, then to the earlier offer.
+    pub fn negotiate_media(&self, offered: &[&'static str]) -> Option<&'static str> {
+        let ranges: Vec<(&str, f32)> = self
+            .accept
+            .split(',')
+            .filter_map(|range| {
+                let mut params = range.split(';');
+                let media = params.next()?.trim();
+                let q = params
+                    .filter_map(|p| p.trim().strip_prefix("q="))
+                    .find_map(|q| q.trim().parse::<f32>().ok())
+                    .unwrap_or(1.0);
+                (!media.is_empty()).then_some((media, q))
+            })
+            .collect();
+
+        let mut best: Option<(&'static str, f32, u8)> = None;
+        for &media in offered {
+            let (kind, _) = media.split_once('/').unwrap_or((media, ""));
+            // The most specific range matching this offer decides its quality
+            let matched = ranges
+                .iter()
+                .filter_map(|&(range, q)| {
+                    let specificity = if range.eq_ignore_ascii_case(media) {
+                        2
+                    } else if range
+                        .strip_suffix("/*")
+                        .is_some_and(|k| k.eq_ignore_ascii_case(kind))
+                    {
+                        1
+                    } else if range == "*/*" {
+                        0
+                    } else {
+                        return None;
+                    };
+                    Some((q, specificity))
+                })
+                .max_by_key(|&(_, specificity)| specificity);
+
+            if let Some((q, specificity)) = matched.filter(|&(q, _)| q > 0.0) {
+                let better = best.is_none_or(|(_, best_q, best_spec)| {
+                    q > best_q || (q == best_q && specificity > best_spec)
+                });
+                if better {
+                    best = Some((media, q, specificity));
+                }
+            }
+        }
+        best.map(|(media, _, _)| media)
+    }
+}
+
+// ════════════════════════════════════════════════════════════
+// 4. Incremental JSON Patches
+// ════════════════════════════════════════════════════════════
+impl SilcrowRequest {
+    /// Builds a versioned JSON response that only carries the changes since
+    /// `previous` when the runtime reports holding exactly that snapshot.
+    /// Any other client (first load, stale state, plain API caller) gets the
+    /// full payload, so `previous` never has to match what the client saw.
+    pub fn json_patch<T: serde::Serialize>(
+        &self,
+        previous: &serde_json::Value,
+        data: T,
+    ) -> JsonResponse<T> {
+        let response = json(data).versioned();
+        match &self.patch_base {
+            Some(base) if *base == diff::version(previous) => {
+                response.diff_from(previous.clone())
+            }
+            _ => response,
+        }
+    }
+}
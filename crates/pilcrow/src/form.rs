@@ -0,0 +1,100 @@
+// ./crates/pilcrow/src/form.rs
+
+use axum::async_trait;
+use axum::body::Bytes;
+use axum::extract::{FromRequest, Multipart, Request};
+use axum::http::{header, Method, StatusCode};
+use serde::de::DeserializeOwned;
+
+use crate::extract::SilcrowRequest;
+use crate::select::{AppError, Rejection};
+
+/// Deserializes a submission regardless of how silcrow.js (or a browser
+/// without it) sent it: `multipart/form-data` from `FormData`, JSON from
+/// `Silcrow.go`, urlencoded forms, or the query string of a GET form.
+///
+/// Form encodings go through `serde_html_form`, so repeated keys fill
+/// `Vec` fields. File parts are skipped. Failures become a 400 (415 for
+/// unknown content types) in the format the request negotiated.
+#[derive(Debug, Clone)]
+pub struct SilcrowForm<T>(pub T);
+
+#[async_trait]
+impl<T, S> FromRequest<S> for SilcrowForm<T>
+where
+    T: DeserializeOwned,
+    S: Send + Sync,
+{
+    type Rejection = AppError;
+
+    async fn from_request(req: Request, state: &S) -> Result<Self, Self::Rejection> {
+        let silcrow = SilcrowRequest::from_headers(req.headers());
+        let reject = |status: StatusCode, message: String| {
+            AppError::Rejected(Rejection::new(status, message, &silcrow))
+        };
+        let invalid = |err: &dyn std::fmt::Display| {
+            reject(StatusCode::BAD_REQUEST, format!("Invalid form data: {err}"))
+        };
+
+        if matches!(*req.method(), Method::GET | Method::HEAD) {
+            let query = req.uri().query().unwrap_or("");
+            return serde_html_form::from_str(query)
+                .map(SilcrowForm)
+                .map_err(|e| invalid(&e));
+        }
+
+        let content_type = req
+            .headers()
+            .get(header::CONTENT_TYPE)
+            .and_then(|v| v.to_str().ok())
+            .unwrap_or("")
+            .to_ascii_lowercase();
+        let mime = content_type.split(';').next().unwrap_or("").trim().to_string();
+
+        if mime == "application/json" || mime.ends_with("+json") {
+            let bytes = Bytes::from_request(req, state)
+                .await
+                .map_err(|e| invalid(&e))?;
+            return serde_json::from_slice(&bytes)
+                .map(SilcrowForm)
+                .map_err(|e| invalid(&e));
+        }
+
+        if mime == "multipart/form-data" {
+            let mut multipart = Multipart::from_request(req, state)
+                .await
+                .map_err(|e| invalid(&e))?;
+
+            // Re-encode the text parts so both form encodings share one deserializer
+            let mut fields = Vec::new();
+            while let Some(field) = multipart.next_field().await.map_err(|e| invalid(&e))? {
+                let Some(name) = field.name().map(str::to_owned) else {
+                    continue;
+                };
+                if field.file_name().is_some() {
+                    continue;
+                }
+                let value = field.text().await.map_err(|e| invalid(&e))?;
+                fields.push((name, value));
+            }
+            let encoded = serde_html_form::to_string(&fields).map_err(|e| invalid(&e))?;
+            return serde_html_form::from_str(&encoded)
+                .map(SilcrowForm)
+                .map_err(|e| invalid(&e));
+        }
+
+        if mime.is_empty() || mime == "application/x-www-form-urlencoded" {
+            let bytes = Bytes::from_request(req, state)
+                .await
+                .map_err(|e| invalid(&e))?;
+            return serde_html_form::from_bytes(&bytes)
+                .map(SilcrowForm)
+                .map_err(|e| invalid(&e));
+        }
+
+        Err(reject(
+            StatusCode::UNSUPPORTED_MEDIA_TYPE,
+            format!("Unsupported form content type `{mime}`"),
+        ))
+    }
+}
@@ -0,0 +1,222 @@
+// ./crates/pilcrow/src/format.rs
+
+use std::convert::Infallible;
+use std::future::Future;
+use std::pin::Pin;
+use std::sync::Arc;
+use std::task::{Context, Poll};
+
+use axum::http::{header, uri::PathAndQuery, HeaderValue, Request, Uri};
+use axum::response::Response;
+use tower_layer::Layer;
+use tower_service::Service;
+
+/// Query parameter that forces a representation, e.g. `?_format=json`.
+pub const FORMAT_PARAM: &str = "_format";
+
+// ════════════════════════════════════════════════════════════
+// 1. The Layer
+// ════════════════════════════════════════════════════════════
+
+/// Lets a plain browser ask for a specific representation through the URL:
+/// `?_format=json`, plus any format registered with
+/// [`format`](FormatOverrideLayer::format). Path extensions (`/orders.json`)
+/// are opt-in via [`extensions`](FormatOverrideLayer::extensions).
+///
+/// Enabled in debug builds only by default.
+///
+/// The override is stripped from the URL and replaces the `Accept` header,
+/// so routing, [`SilcrowRequest`](crate::SilcrowRequest) and every layer
+/// below see an ordinary negotiated request. Since the URL now selects the
+/// representation, `Accept` is dropped from the response's `Vary`.
+///
+/// Routing has to see the rewritten path, so this wraps the whole router
+/// rather than going through `Router::layer`:
+///
+/// ```ignore
+/// let app = FormatOverrideLayer::new().extensions(true).layer(router);
+/// axum::serve(listener, axum::ServiceExt::<Request>::into_make_service(app)).await?;
+/// ```
+#[derive(Debug, Clone)]
+pub struct FormatOverrideLayer {
+    enabled: bool,
+    extensions: bool,
+    formats: Vec<(String, &'static str)>,
+}
+
+impl Default for FormatOverrideLayer {
+    fn default() -> Self {
+        FormatOverrideLayer {
+            enabled: cfg!(debug_assertions),
+            extensions: false,
+            formats: vec![
+                ("json".to_string(), "application/json"),
+                ("html".to_string(), "text/html"),
+            ],
+        }
+    }
+}
+
+impl FormatOverrideLayer {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Turns the overrides on or off; defaults to `cfg!(debug_assertions)`
+    /// so they stay out of production. Disabled, requests pass through untouched.
+    pub fn enabled(mut self, enabled: bool) -> Self {
+        self.enabled = enabled;
+        self
+    }
+
+    /// Also honours `.json`-style path extensions. Off by default: with it
+    /// on, real routes such as `/openapi.json` lose their extension too.
+    pub fn extensions(mut self, enabled: bool) -> Self {
+        self.extensions = enabled;
+        self
+    }
+
+    /// Maps `?_format=<name>` and a `.<name>` extension to `media_type`,
+    /// e.g. `format("csv", "text/csv")` for a [`Responses::media`](crate::Responses::media) slot.
+    pub fn format(mut self, name: impl Into<String>, media_type: &'static str) -> Self {
+        let name = name.into().to_ascii_lowercase();
+        self.formats.retain(|(existing, _)| *existing != name);
+        self.formats.push((name, media_type));
+        self
+    }
+}
+
+impl<S> Layer<S> for FormatOverrideLayer {
+    type Service = FormatOverrideService<S>;
+
+    fn layer(&self, inner: S) -> Self::Service {
+        FormatOverrideService {
+            inner,
+            formats: self.enabled.then(|| Arc::new(self.formats.clone())),
+            extensions: self.extensions,
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct FormatOverrideService<S> {
+    inner: S,
+    /// `None` when disabled.
+    formats: Option<Arc<Vec<(String, &'static str)>>>,
+    extensions: bool,
+}
+
+impl<S, B> Service<Request<B>> for FormatOverrideService<S>
+where
+    S: Service<Request<B>, Response = Response, Error = Infallible> + Clone + Send + 'static,
+    S::Future: Send + 'static,
+    B: Send + 'static,
+{
+    type Response = Response;
+    type Error = Infallible;
+    type Future = Pin<Box<dyn Future<Output = Result<Response, Infallible>> + Send>>;
+
+    fn poll_ready(&mut self, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        self.inner.poll_ready(cx)
+    }
+
+    fn call(&mut self, mut request: Request<B>) -> Self::Future {
+        let media = self
+            .formats
+            .as_ref()
+            .and_then(|formats| apply_override(&mut request, formats, self.extensions));
+
+        let clone = self.inner.clone();
+        let mut inner = std::mem::replace(&mut self.inner, clone);
+
+        Box::pin(async move {
+            let mut response = inner.call(request).await?;
+            if media.is_some() {
+                remove_vary(&mut response, "Accept");
+            }
+            Ok(response)
+        })
+    }
+}
+
+// ════════════════════════════════════════════════════════════
+// 2. Rewriting
+// ════════════════════════════════════════════════════════════
+
+fn parse_format(formats: &[(String, &'static str)], format: &str) -> Option<&'static str> {
+    formats
+        .iter()
+        .find(|(name, _)| name.eq_ignore_ascii_case(format))
+        .map(|(_, media)| *media)
+}
+
+/// Strips a recognised override from the URI and rewrites `Accept` to match.
+fn apply_override<B>(
+    request: &mut Request<B>,
+    formats: &[(String, &'static str)],
+    extensions: bool,
+) -> Option<&'static str> {
+    let uri = request.uri().clone();
+    let mut path = uri.path().to_string();
+    let mut query: Vec<&str> = uri.query().unwrap_or("").split('&').filter(|p| !p.is_empty()).collect();
+
+    let mut mode = None;
+    // The query parameter wins over the extension
+    if let Some(index) = query.iter().position(|pair| {
+        pair.split_once('=').map_or(*pair, |(key, _)| key) == FORMAT_PARAM
+    }) {
+        let value = query[index].split_once('=').map_or("", |(_, v)| v);
+        mode = parse_format(formats, value);
+        if mode.is_some() {
+            query.remove(index);
+        }
+    }
+
+    let last = path.rsplit('/').next().unwrap_or("");
+    if let Some((stem, ext)) = last.rsplit_once('.').filter(|_| extensions) {
+        if !stem.is_empty() {
+            if let Some(from_ext) = parse_format(formats, ext) {
+                path.truncate(path.len() - ext.len() - 1);
+                mode = mode.or(Some(from_ext));
+            }
+        }
+    }
+
+    let mode = mode?;
+    let path_and_query = if query.is_empty() {
+        path
+    } else {
+        format!("{path}?{}", query.join("&"))
+    };
+
+    let mut parts = uri.into_parts();
+    parts.path_and_query = PathAndQuery::try_from(path_and_query).ok();
+    if let Ok(rewritten) = Uri::from_parts(parts) {
+        *request.uri_mut() = rewritten;
+    }
+
+    request
+        .headers_mut()
+        .insert(header::ACCEPT, HeaderValue::from_static(mode));
+    Some(mode)
+}
+
+fn remove_vary(response: &mut Response, name: &str) {
+    let vary: Vec<String> = response
+        .headers()
+        .get_all(header::VARY)
+        .iter()
+        .filter_map(|v| v.to_str().ok())
+        .flat_map(|v| v.split(','))
+        .map(|v| v.trim().to_string())
+        .filter(|v| !v.is_empty() && !v.eq_ignore_ascii_case(name))
+        .collect();
+
+    response.headers_mut().remove(header::VARY);
+    if vary.is_empty() {
+        return;
+    }
+    if let Ok(value) = HeaderValue::from_str(&vary.join(", ")) {
+        response.headers_mut().insert(header::VARY, value);
+    }
+}
@@ -0,0 +1,140 @@
+// ./crates/pilcrow/src/fragment.rs
+
+use std::collections::BTreeMap;
+use std::sync::Arc;
+
+use axum::response::{IntoResponse, Response};
+
+use crate::extract::SilcrowRequest;
+use crate::select::AppError;
+
+type Renderer<C> = Arc<dyn Fn(&C) -> Result<Response, AppError> + Send + Sync>;
+
+fn boxed<C, R, F>(render: F) -> Renderer<C>
+where
+    F: Fn(&C) -> Result<R, AppError> + Send + Sync + 'static,
+    R: IntoResponse,
+{
+    Arc::new(move |ctx| render(ctx).map(IntoResponse::into_response))
+}
+
+// ════════════════════════════════════════════════════════════
+// 1. The Registry
+// ════════════════════════════════════════════════════════════
+
+/// The partials a page can render, keyed by the `s-target` selector that
+/// asks for them.
+///
+/// Every renderer receives the same context (usually the page's view
+/// model), so a handler loads its data once and lets the registry pick:
+/// a Silcrow request targeting `#cart` gets the `#cart` fragment, anything
+/// else gets the full page.
+pub struct FragmentRegistry<C> {
+    page: Renderer<C>,
+    fragments: BTreeMap<String, Renderer<C>>,
+}
+
+impl<C> Clone for FragmentRegistry<C> {
+    fn clone(&self) -> Self {
+        FragmentRegistry {
+            page: self.page.clone(),
+            fragments: self.fragments.clone(),
+        }
+    }
+}
+
+impl<C> FragmentRegistry<C> {
+    pub fn new<R, F>(page: F) -> Self
+    where
+        F: Fn(&C) -> Result<R, AppError> + Send + Sync + 'static,
+        R: IntoResponse,
+    {
+        FragmentRegistry {
+            page: boxed(page),
+            fragments: BTreeMap::new(),
+        }
+    }
+
+    /// Declares the renderer used when silcrow.js targets `selector`.
+    pub fn fragment<R, F>(mut self, selector: impl Into<String>, render: F) -> Self
+    where
+        F: Fn(&C) -> Result<R, AppError> + Send + Sync + 'static,
+        R: IntoResponse,
+    {
+        self.fragments.insert(selector.into().trim().to_string(), boxed(render));
+        self
+    }
+
+    pub fn selectors(&self) -> impl Iterator<Item = &str> {
+        self.fragments.keys().map(String::as_str)
+    }
+
+    /// Renders the fragment matching the request's target, or the full page.
+    pub fn render(&self, req: &SilcrowRequest, ctx: &C) -> Result<Response, AppError> {
+        let fragment = req
+            .target
+            .as_deref()
+            .filter(|_| req.is_silcrow)
+            .and_then(|target| self.fragments.get(target.trim()));
+
+        match fragment {
+            Some(render) => render(ctx),
+            None => (self.page)(ctx),
+        }
+    }
+}
+
+// ════════════════════════════════════════════════════════════
+// 2. Test Support
+// ════════════════════════════════════════════════════════════
+
+impl<C> FragmentRegistry<C> {
+    /// Renders the page and every declared fragment against `ctx`, panicking
+    /// with the offending selectors if any fails or answers with a non-2xx
+    /// status. Meant for tests.
+    ///
+    /// ```
+    /// use pilcrow::{html, AppError, FragmentRegistry};
+    ///
+    /// struct Cart {
+    ///     items: Vec<String>,
+    /// }
+    ///
+    /// let registry = FragmentRegistry::new(|cart: &Cart| {
+    ///     Ok::<_, AppError>(html(format!("<main>{} items</main>", cart.items.len())))
+    /// })
+    /// .fragment("#cart", |cart: &Cart| Ok::<_, AppError>(html(cart.items.join(", "))));
+    ///
+    /// registry.assert_renders(&Cart { items: vec!["tea".into()] });
+    /// ```
+    ///
+    /// ```should_panic
+    /// use pilcrow::{html, AppError, FragmentRegistry};
+    ///
+    /// let registry = FragmentRegistry::new(|_: &()| Ok::<_, AppError>(html("<main></main>")))
+    ///     .fragment("#cart", |_: &()| Err::<String, _>(AppError::NotFound("cart".into())));
+    ///
+    /// registry.assert_renders(&());
+    /// ```
+    #[track_caller]
+    pub fn assert_renders(&self, ctx: &C) {
+        let renderers = std::iter::once(("<page>", &self.page))
+            .chain(self.fragments.iter().map(|(s, r)| (s.as_str(), r)));
+
+        let failures: Vec<String> = renderers
+            .filter_map(|(selector, render)| match render(ctx) {
+                Ok(response) if response.status().is_success() => None,
+                Ok(response) => Some(format!("{selector}: status {}", response.status())),
+                Err(err) => Some(format!("{selector}: {err}")),
+            })
+            .collect();
+
+        if !failures.is_empty() {
+            panic!(
+                "{} fragment(s) failed to render:\n  {}",
+                failures.len(),
+                failures.join("\n  ")
+            );
+        }
+    }
+}
@@ -0,0 +1,425 @@
+// ./crates/pilcrow/src/idempotency.rs
+
+use std::collections::HashMap;
+use std::future::Future;
+use std::pin::Pin;
+use std::sync::{Arc, Mutex};
+use std::task::{Context, Poll};
+use std::time::{Duration, Instant};
+
+use axum::async_trait;
+use axum::body::{Body, Bytes, HttpBody};
+use axum::http::{header, Extensions, HeaderMap, HeaderValue, Method, Request, StatusCode};
+use axum::response::{IntoResponse, Response};
+use tower_layer::Layer;
+use tower_service::Service;
+
+use crate::extract::SilcrowRequest;
+use crate::select::{AppError, Rejection};
+
+/// Header silcrow.js sends a per-submission key in on every non-GET navigation.
+pub const IDEMPOTENCY_HEADER: &str = "idempotency-key";
+/// Set on responses replayed from the store.
+pub const REPLAYED_HEADER: &str = "idempotent-replayed";
+/// Set on the 409 for a duplicate of a request still in flight; silcrow.js
+/// drops these quietly since the original will do the swap.
+pub const DUPLICATE_HEADER: &str = "silcrow-duplicate";
+
+/// Responses larger than this, or of unknown length, are passed through but never stored.
+const MAX_STORED_BYTES: usize = 1024 * 1024;
+/// Request bodies up to this size are hashed into the fingerprint.
+const MAX_HASHED_BYTES: usize = 1024 * 1024;
+
+// ════════════════════════════════════════════════════════════
+// 1. The Store
+// ════════════════════════════════════════════════════════════
+
+/// A response captured for replay.
+#[derive(Debug, Clone)]
+pub struct StoredResponse {
+    pub status: StatusCode,
+    pub headers: HeaderMap,
+    pub body: Bytes,
+    /// Markers for middleware further out, e.g. the layout a page still
+    /// asks for. Only in-process stores can keep these.
+    pub extensions: Extensions,
+}
+
+/// What the store knows about a key when a request claims it.
+#[derive(Debug, Clone)]
+pub enum Claim {
+    /// First time the key is seen; the caller now owns it.
+    Acquired,
+    /// Another request with this key is still running.
+    InFlight,
+    /// The key finished before; replay this.
+    Completed(StoredResponse),
+    /// The key was used for a different request.
+    Mismatch,
+}
+
+/// Backing storage for [`IdempotencyLayer`]; swap in a shared store
+/// (Redis, a database table, ...) when running more than one instance.
+#[async_trait]
+pub trait IdempotencyStore: Send + Sync + 'static {
+    /// Atomically claims `key` for the request identified by `fingerprint`.
+    /// An unfinished claim lapses after `lease`, in case its request never completes.
+    async fn claim(&self, key: &str, fingerprint: &str, lease: Duration) -> Claim;
+    /// Records the response of a claimed key, kept for `ttl`.
+    async fn complete(&self, key: &str, response: StoredResponse, ttl: Duration);
+    /// Releases a claimed key without a response, so a retry runs again.
+    async fn release(&self, key: &str);
+}
+
+enum Entry {
+    InFlight(Instant),
+    Completed(StoredResponse, Instant),
+}
+
+/// An in-process store; the default.
+#[derive(Default)]
+pub struct MemoryStore {
+    entries: Mutex<HashMap<String, (String, Entry)>>,
+}
+
+impl MemoryStore {
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+#[async_trait]
+impl IdempotencyStore for MemoryStore {
+    async fn claim(&self, key: &str, fingerprint: &str, lease: Duration) -> Claim {
+        let mut entries = self.entries.lock().unwrap_or_else(|e| e.into_inner());
+        let now = Instant::now();
+        entries.retain(|_, (_, entry)| match entry {
+            Entry::InFlight(expires) | Entry::Completed(_, expires) => *expires > now,
+        });
+
+        match entries.get(key) {
+            Some((seen, _)) if seen != fingerprint => Claim::Mismatch,
+            Some((_, Entry::InFlight(_))) => Claim::InFlight,
+            Some((_, Entry::Completed(response, _))) => Claim::Completed(response.clone()),
+            None => {
+                let entry = Entry::InFlight(now + lease);
+                entries.insert(key.to_string(), (fingerprint.to_string(), entry));
+                Claim::Acquired
+            }
+        }
+    }
+
+    async fn complete(&self, key: &str, response: StoredResponse, ttl: Duration) {
+        let mut entries = self.entries.lock().unwrap_or_else(|e| e.into_inner());
+        if let Some((_, entry)) = entries.get_mut(key) {
+            *entry = Entry::Completed(response, Instant::now() + ttl);
+        }
+    }
+
+    async fn release(&self, key: &str) {
+        let mut entries = self.entries.lock().unwrap_or_else(|e| e.into_inner());
+        entries.remove(key);
+    }
+}
+
+// ════════════════════════════════════════════════════════════
+// 2. The Middleware
+// ════════════════════════════════════════════════════════════
+
+/// Deduplicates unsafe requests carrying an `Idempotency-Key`.
+///
+/// The first request with a key runs and its response is stored; repeats
+/// get that response replayed, a repeat arriving while the first is still
+/// running gets a 409, and reusing a key for a different request (method,
+/// path, query, content type or body) a 400. Server errors, abandoned
+/// requests and streamed or oversized responses are not stored, so a retry
+/// after one runs again. Requests without the header pass straight through.
+#[derive(Clone)]
+pub struct IdempotencyLayer {
+    store: Arc<dyn IdempotencyStore>,
+    ttl: Duration,
+    lease: Duration,
+}
+
+impl Default for IdempotencyLayer {
+    fn default() -> Self {
+        IdempotencyLayer {
+            store: Arc::new(MemoryStore::new()),
+            ttl: Duration::from_secs(60 * 60),
+            lease: Duration::from_secs(5 * 60),
+        }
+    }
+}
+
+impl IdempotencyLayer {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn store(mut self, store: impl IdempotencyStore) -> Self {
+        self.store = Arc::new(store);
+        self
+    }
+
+    /// How long completed responses are replayed. Defaults to one hour.
+    pub fn ttl(mut self, ttl: Duration) -> Self {
+        self.ttl = ttl;
+        self
+    }
+
+    /// How long a request may hold its key before a retry can claim it
+    /// again. Defaults to five minutes.
+    pub fn lease(mut self, lease: Duration) -> Self {
+        self.lease = lease;
+        self
+    }
+}
+
+impl<S> Layer<S> for IdempotencyLayer {
+    type Service = IdempotencyService<S>;
+
+    fn layer(&self, inner: S) -> Self::Service {
+        IdempotencyService {
+            inner,
+            store: self.store.clone(),
+            ttl: self.ttl,
+            lease: self.lease,
+        }
+    }
+}
+
+#[derive(Clone)]
+pub struct IdempotencyService<S> {
+    inner: S,
+    store: Arc<dyn IdempotencyStore>,
+    ttl: Duration,
+    lease: Duration,
+}
+
+impl<S> Service<Request<Body>> for IdempotencyService<S>
+where
+    S: Service<Request<Body>, Response = Response> + Clone + Send + 'static,
+    S::Future: Send + 'static,
+    S::Error: Send,
+{
+    type Response = Response;
+    type Error = S::Error;
+    type Future = Pin<Box<dyn Future<Output = Result<Response, S::Error>> + Send>>;
+
+    fn poll_ready(&mut self, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        self.inner.poll_ready(cx)
+    }
+
+    fn call(&mut self, request: Request<Body>) -> Self::Future {
+        let store = self.store.clone();
+        let ttl = self.ttl;
+        let lease = self.lease;
+        let clone = self.inner.clone();
+        let mut inner = std::mem::replace(&mut self.inner, clone);
+
+        Box::pin(async move {
+            let is_safe = matches!(
+                *request.method(),
+                Method::GET | Method::HEAD | Method::OPTIONS | Method::TRACE
+            );
+            let key = request
+                .headers()
+                .get(IDEMPOTENCY_HEADER)
+                .and_then(|v| v.to_str().ok())
+                .filter(|v| !v.is_empty() && v.len() <= 255)
+                .map(str::to_owned);
+            let Some(key) = key.filter(|_| !is_safe) else {
+                return inner.call(request).await;
+            };
+
+            let silcrow = SilcrowRequest::from_headers(request.headers());
+            let (request, fingerprint) = match fingerprint(request).await {
+                Ok(fingerprinted) => fingerprinted,
+                Err(err) => return Ok(err.into_response()),
+            };
+
+            match store.claim(&key, &fingerprint, lease).await {
+                Claim::Acquired => {}
+                Claim::Completed(stored) => return Ok(replay(stored)),
+                Claim::InFlight => {
+                    let mut response = Rejection::new(
+                        StatusCode::CONFLICT,
+                        "This request is already being processed",
+                        &silcrow,
+                    )
+                    .into_response();
+                    response
+                        .headers_mut()
+                        .insert(DUPLICATE_HEADER, HeaderValue::from_static("true"));
+                    return Ok(response);
+                }
+                // Not a 422: silcrow.js would swap that in as a validation result
+                Claim::Mismatch => {
+                    return Ok(Rejection::new(
+                        StatusCode::BAD_REQUEST,
+                        "Idempotency key was already used for another request",
+                        &silcrow,
+                    )
+                    .into_response());
+                }
+            }
+
+            // Releases the key if this future is dropped before it settles
+            let mut guard = ClaimGuard {
+                store: store.clone(),
+                key: Some(key.clone()),
+            };
+
+            let response = match inner.call(request).await {
+                Ok(response) => response,
+                Err(err) => {
+                    guard.disarm();
+                    store.release(&key).await;
+                    return Err(err);
+                }
+            };
+
+            let storable_size = response
+                .body()
+                .size_hint()
+                .exact()
+                .filter(|&size| size <= MAX_STORED_BYTES as u64);
+            if response.status().is_server_error() || storable_size.is_none() {
+                guard.disarm();
+                store.release(&key).await;
+                return Ok(response);
+            }
+
+            let (parts, body) = response.into_parts();
+            let bytes = match axum::body::to_bytes(body, MAX_STORED_BYTES).await {
+                Ok(bytes) => bytes,
+                Err(err) => {
+                    guard.disarm();
+                    store.release(&key).await;
+                    let err = anyhow::anyhow!("Failed to buffer response body: {err}");
+                    return Ok(AppError::Internal(err).into_response());
+                }
+            };
+
+            let stored = StoredResponse {
+                status: parts.status,
+                headers: parts.headers.clone(),
+                body: bytes.clone(),
+                extensions: parts.extensions.clone(),
+            };
+            guard.disarm();
+            store.complete(&key, stored, ttl).await;
+            Ok(Response::from_parts(parts, Body::from(bytes)))
+        })
+    }
+}
+
+/// Releases a claimed key when the request future is dropped mid-flight,
+/// e.g. on client disconnect or a timeout.
+struct ClaimGuard {
+    store: Arc<dyn IdempotencyStore>,
+    key: Option<String>,
+}
+
+impl ClaimGuard {
+    fn disarm(&mut self) {
+        self.key = None;
+    }
+}
+
+impl Drop for ClaimGuard {
+    fn drop(&mut self) {
+        let Some(key) = self.key.take() else {
+            return;
+        };
+        // Without a runtime the lease still frees the key eventually
+        if let Ok(runtime) = tokio::runtime::Handle::try_current() {
+            let store = self.store.clone();
+            runtime.spawn(async move { store.release(&key).await });
+        }
+    }
+}
+
+/// Identifies what a key was used for: method, path, query, content type
+/// and a hash of the body. Bodies without a known length, or over
+/// `MAX_HASHED_BYTES`, contribute their declared length only.
+///
+/// Multipart boundaries are dropped from both, since `FormData` picks a
+/// fresh one per submission and a double submit would otherwise never match.
+async fn fingerprint(request: Request<Body>) -> Result<(Request<Body>, String), AppError> {
+    let content_type = request
+        .headers()
+        .get(header::CONTENT_TYPE)
+        .and_then(|v| v.to_str().ok())
+        .unwrap_or("");
+    let boundary = multer::parse_boundary(content_type)
+        .ok()
+        .filter(|b| !b.is_empty());
+    let content_type = match boundary {
+        Some(_) => content_type.split(';').next().unwrap_or("").trim(),
+        None => content_type,
+    };
+    let mut fingerprint = format!(
+        "{} {}?{} {content_type}",
+        request.method(),
+        request.uri().path(),
+        request.uri().query().unwrap_or("")
+    );
+
+    let size = request.body().size_hint().exact();
+    if size.is_none_or(|size| size > MAX_HASHED_BYTES as u64) {
+        let length = request
+            .headers()
+            .get(header::CONTENT_LENGTH)
+            .and_then(|v| v.to_str().ok())
+            .unwrap_or("");
+        fingerprint.push_str(&format!(" len:{length}"));
+        return Ok((request, fingerprint));
+    }
+
+    let (parts, body) = request.into_parts();
+    let bytes = axum::body::to_bytes(body, MAX_HASHED_BYTES)
+        .await
+        .map_err(|err| anyhow::anyhow!("Failed to buffer request body: {err}"))?;
+    let digest = match &boundary {
+        Some(boundary) => hash(&strip(&bytes, boundary.as_bytes())),
+        None => hash(&bytes),
+    };
+    fingerprint.push_str(&format!(" {digest}"));
+    Ok((Request::from_parts(parts, Body::from(bytes)), fingerprint))
+}
+
+/// `bytes` with every occurrence of `needle` removed.
+fn strip(bytes: &[u8], needle: &[u8]) -> Vec<u8> {
+    let mut out = Vec::with_capacity(bytes.len());
+    let mut rest = bytes;
+    while let Some(at) = rest.windows(needle.len()).position(|w| w == needle) {
+        out.extend_from_slice(&rest[..at]);
+        rest = &rest[at + needle.len()..];
+    }
+    out.extend_from_slice(rest);
+    out
+}
+
+/// FNV-1a, like [`diff::version`](crate::diff::version): stable across
+/// processes, so shared stores can compare fingerprints.
+fn hash(bytes: &[u8]) -> String {
+    const OFFSET: u64 = 0xcbf2_9ce4_8422_2325;
+    const PRIME: u64 = 0x0000_0100_0000_01b3;
+
+    let hash = bytes
+        .iter()
+        .fold(OFFSET, |hash, byte| (hash ^ *byte as u64).wrapping_mul(PRIME));
+    format!("{hash:016x}")
+}
+
+fn replay(stored: StoredResponse) -> Response {
+    let mut response = Response::new(Body::from(stored.body));
+    *response.status_mut() = stored.status;
+    *response.headers_mut() = stored.headers;
+    *response.extensions_mut() = stored.extensions;
+    response
+        .headers_mut()
+        .insert(REPLAYED_HEADER, HeaderValue::from_static("true"));
+    response
+}
@@ -0,0 +1,167 @@
+// ./crates/pilcrow/src/keyed.rs
+
+use std::collections::HashSet;
+
+use serde::ser::{Error as _, SerializeSeq};
+use serde::{Serialize, Serializer};
+use serde_json::Value;
+
+pub use pilcrow_macros::Keyed;
+
+use crate::render::js_string;
+
+// ════════════════════════════════════════════════════════════
+// 1. Keyed Trait
+// ════════════════════════════════════════════════════════════
+
+/// An item that can be rendered into an `s-list` container.
+///
+/// silcrow.js reconciles list items by their `key` field, so keys must be
+/// stable across responses and unique within one collection. A key of the
+/// form `tpl#id` selects `<template id="tpl">` for that item.
+pub trait Keyed {
+    fn key(&self) -> String;
+}
+
+impl<T: Keyed + ?Sized> Keyed for &T {
+    fn key(&self) -> String {
+        (**self).key()
+    }
+}
+
+impl<T: Keyed + ?Sized> Keyed for Box<T> {
+    fn key(&self) -> String {
+        (**self).key()
+    }
+}
+
+// ════════════════════════════════════════════════════════════
+// 2. Keyed Collections
+// ════════════════════════════════════════════════════════════
+
+/// A collection serialized the way `s-list` expects it: every item becomes
+/// an object carrying its [`Keyed::key`] under `key`.
+///
+/// Serialization fails on items that are not objects or on duplicate keys,
+/// which surfaces as a 500 from [`json`](crate::json) instead of a list the
+/// runtime would silently refuse to patch.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct KeyedList<T>(pub Vec<T>);
+
+/// Wraps `items` in a [`KeyedList`].
+pub fn keyed<T: Keyed>(items: impl IntoIterator<Item = T>) -> KeyedList<T> {
+    KeyedList(items.into_iter().collect())
+}
+
+impl<T> KeyedList<T> {
+    pub fn into_inner(self) -> Vec<T> {
+        self.0
+    }
+}
+
+impl<T: Keyed> FromIterator<T> for KeyedList<T> {
+    fn from_iter<I: IntoIterator<Item = T>>(iter: I) -> Self {
+        keyed(iter)
+    }
+}
+
+impl<T: Keyed> From<Vec<T>> for KeyedList<T> {
+    fn from(items: Vec<T>) -> Self {
+        KeyedList(items)
+    }
+}
+
+impl<T: Keyed + Serialize> Serialize for KeyedList<T> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let mut seen = HashSet::with_capacity(self.0.len());
+        let mut seq = serializer.serialize_seq(Some(self.0.len()))?;
+
+        for item in &self.0 {
+            let key = item.key();
+            if !seen.insert(key.clone()) {
+                return Err(S::Error::custom(format!("duplicate s-list key `{key}`")));
+            }
+
+            let mut value = serde_json::to_value(item).map_err(S::Error::custom)?;
+            let Value::Object(map) = &mut value else {
+                return Err(S::Error::custom(format!(
+                    "s-list item `{key}` must serialize to an object"
+                )));
+            };
+            map.insert("key".to_string(), Value::String(key));
+            seq.serialize_element(&value)?;
+        }
+
+        seq.end()
+    }
+}
+
+// ════════════════════════════════════════════════════════════
+// 3. Debug Checks
+// ════════════════════════════════════════════════════════════
+
+/// Warns about arrays that look like `s-list` data but that the runtime
+/// would reject: some items carry `key` while others lack it, or two items
+/// share a key. Only compiled into debug builds.
+#[cfg(debug_assertions)]
+pub(crate) fn check_payload(value: &Value) {
+    check_at(value, &mut String::new());
+}
+
+#[cfg(debug_assertions)]
+fn check_at(value: &Value, path: &mut String) {
+    match value {
+        Value::Object(map) => {
+            for (field, child) in map {
+                let len = path.len();
+                if !path.is_empty() {
+                    path.push('.');
+                }
+                path.push_str(field);
+                check_at(child, path);
+                path.truncate(len);
+            }
+        }
+        Value::Array(items) => {
+            check_collection(items, path);
+            for (index, child) in items.iter().enumerate() {
+                let len = path.len();
+                path.push_str(&format!("[{index}]"));
+                check_at(child, path);
+                path.truncate(len);
+            }
+        }
+        _ => {}
+    }
+}
+
+#[cfg(debug_assertions)]
+fn check_collection(items: &[Value], path: &str) {
+    let keyed = |item: &Value| item.get("key").is_some_and(|key| !key.is_null());
+    if !items.iter().any(keyed) {
+        return;
+    }
+    let path = if path.is_empty() { "<root>" } else { path };
+
+    let missing = items.iter().filter(|item| !keyed(item)).count();
+    if missing > 0 {
+        tracing::warn!(
+            path,
+            missing,
+            "s-list data has items without a `key`; silcrow.js will not reconcile this list"
+        );
+    }
+
+    let mut seen = HashSet::new();
+    for key in items.iter().filter_map(|item| item.get("key")) {
+        let key = js_string(key);
+        if !key.is_empty() && !seen.insert(key.clone()) {
+            tracing::warn!(
+                path,
+                key,
+                "s-list data has duplicate keys; silcrow.js will not reconcile this list"
+            );
+            return;
+        }
+    }
+}
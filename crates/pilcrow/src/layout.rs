@@ -0,0 +1,188 @@
+// ./crates/pilcrow/src/layout.rs
+
+use std::future::Future;
+use std::pin::Pin;
+use std::sync::Arc;
+use std::task::{Context, Poll};
+
+use axum::body::Body;
+use axum::http::{header, Request, StatusCode};
+use axum::response::{IntoResponse, Response};
+use tower_layer::Layer;
+use tower_service::Service;
+
+use crate::extract::SilcrowRequest;
+use crate::page::PageHead;
+
+// ════════════════════════════════════════════════════════════
+// 1. Layouts
+// ════════════════════════════════════════════════════════════
+
+/// Wraps a page body in the document shell (`<html>`, `<head>`, navigation…).
+#[derive(Clone)]
+pub struct Layout(Arc<dyn Fn(String) -> String + Send + Sync>);
+
+impl Layout {
+    pub fn new<F>(wrap: F) -> Self
+    where
+        F: Fn(String) -> String + Send + Sync + 'static,
+    {
+        Layout(Arc::new(wrap))
+    }
+
+    pub fn wrap(&self, body: String) -> String {
+        (self.0)(body)
+    }
+}
+
+impl std::fmt::Debug for Layout {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str("Layout")
+    }
+}
+
+/// How an [`HtmlResponse`](crate::response::HtmlResponse) asks to be wrapped.
+#[derive(Debug, Clone, Default)]
+pub enum LayoutChoice {
+    /// Use the layouts registered with [`LayoutLayer`] (the default).
+    #[default]
+    Inherit,
+    /// Use this layout instead of the innermost registered one.
+    Use(Layout),
+    /// Never wrap, e.g. for a body that already is a full document.
+    Bare,
+}
+
+// ════════════════════════════════════════════════════════════
+// 2. Middleware
+// ════════════════════════════════════════════════════════════
+
+/// Wraps HTML responses in a [`Layout`] on hard loads and leaves them bare
+/// for Silcrow swaps, which only need the fragment.
+///
+/// Layers nest: a layout registered on a nested router wraps the page first
+/// and the app-wide layout wraps the result. Only responses built with
+/// [`html`](crate::html) or [`page`](crate::page()) are wrapped; a page's
+/// head is merged into the first layout that renders a `</head>`.
+#[derive(Clone, Debug)]
+pub struct LayoutLayer {
+    layout: Layout,
+    standalone: bool,
+}
+
+impl LayoutLayer {
+    pub fn new(layout: Layout) -> Self {
+        LayoutLayer {
+            layout,
+            standalone: false,
+        }
+    }
+
+    /// Replaces outer layouts instead of nesting inside them, for routes
+    /// with their own shell (login pages, print views).
+    pub fn standalone(layout: Layout) -> Self {
+        LayoutLayer {
+            layout,
+            standalone: true,
+        }
+    }
+}
+
+impl<S> Layer<S> for LayoutLayer {
+    type Service = LayoutService<S>;
+
+    fn layer(&self, inner: S) -> Self::Service {
+        LayoutService {
+            inner,
+            layer: self.clone(),
+        }
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct LayoutService<S> {
+    inner: S,
+    layer: LayoutLayer,
+}
+
+impl<S, B> Service<Request<B>> for LayoutService<S>
+where
+    S: Service<Request<B>, Response = Response> + Clone + Send + 'static,
+    S::Future: Send + 'static,
+    B: Send + 'static,
+{
+    type Response = Response;
+    type Error = S::Error;
+    type Future = Pin<Box<dyn Future<Output = Result<Response, S::Error>> + Send>>;
+
+    fn poll_ready(&mut self, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        self.inner.poll_ready(cx)
+    }
+
+    fn call(&mut self, request: Request<B>) -> Self::Future {
+        let is_silcrow = SilcrowRequest::from_headers(request.headers()).is_silcrow;
+        let layer = self.layer.clone();
+
+        // The clone that was driven to readiness must handle this request
+        let clone = self.inner.clone();
+        let mut inner = std::mem::replace(&mut self.inner, clone);
+
+        Box::pin(async move {
+            let mut response = inner.call(request).await?;
+            let page = response.extensions_mut().remove::<PageHead>();
+            let choice = response.extensions_mut().remove::<LayoutChoice>();
+
+            // Dropping the markers keeps outer layers from wrapping too
+            if is_silcrow {
+                if let Some(body) = page.and_then(|page| page.body) {
+                    response = replace_body(response, body);
+                }
+                return Ok(response);
+            }
+
+            let layout = match choice {
+                None | Some(LayoutChoice::Bare) => return Ok(response),
+                Some(LayoutChoice::Use(layout)) => layout,
+                Some(LayoutChoice::Inherit) => layer.layout,
+            };
+
+            // A page's standalone document is swapped for its bare body
+            let (response, body) = match page.as_ref().and_then(|page| page.body.clone()) {
+                Some(body) => (response, body),
+                None => match read_body(response).await {
+                    Ok(parts) => parts,
+                    Err(response) => return Ok(response),
+                },
+            };
+
+            let mut document = layout.wrap(body);
+            let pending = page.filter(|page| !page.inject(&mut document));
+
+            let mut response = replace_body(response, document);
+            if !layer.standalone {
+                response.extensions_mut().insert(LayoutChoice::Inherit);
+                if let Some(mut page) = pending {
+                    // An outer layout may still provide the `<head>`
+                    page.body = None;
+                    response.extensions_mut().insert(page);
+                }
+            }
+            Ok(response)
+        })
+    }
+}
+
+async fn read_body(response: Response) -> Result<(Response, String), Response> {
+    let (parts, body) = response.into_parts();
+    let Ok(bytes) = axum::body::to_bytes(body, usize::MAX).await else {
+        return Err(StatusCode::INTERNAL_SERVER_ERROR.into_response());
+    };
+    let body = String::from_utf8_lossy(&bytes).into_owned();
+    Ok((Response::from_parts(parts, Body::empty()), body))
+}
+
+fn replace_body(response: Response, body: String) -> Response {
+    let (mut parts, _) = response.into_parts();
+    parts.headers.remove(header::CONTENT_LENGTH);
+    Response::from_parts(parts, Body::from(body))
+}
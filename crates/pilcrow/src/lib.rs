@@ -1,14 +1,58 @@
 // ./crates/pilcrow/src/lib.rs
 
 pub mod assets;
+pub mod cache;
+pub mod csrf;
+pub mod diff;
+mod dom;
 pub mod extract;
+pub mod form;
+pub mod format;
+pub mod fragment;
+pub mod idempotency;
+pub mod keyed;
+pub mod layout;
+#[cfg(feature = "maud")]
+pub mod maud;
+pub mod mutation;
+pub mod negotiate;
+pub mod page;
+pub mod render;
 pub mod response;
+pub mod route;
+pub mod router;
+pub mod schema;
 pub mod select;
+pub mod stream;
+pub mod template;
+pub mod upload;
+pub mod validate;
+pub mod version;
 
 // Re-export the core API so developers can just `use pilcrow::*`
+pub use assets::ScriptTag;
+pub use cache::{CacheLayer, ResponseCache};
+pub use csrf::{CsrfLayer, CsrfToken};
 pub use extract::SilcrowRequest;
-pub use response::{html, json, navigate, ResponseExt};
-pub use select::{AppError, Responses};
+pub use form::SilcrowForm;
+pub use format::FormatOverrideLayer;
+pub use fragment::FragmentRegistry;
+pub use idempotency::IdempotencyLayer;
+pub use keyed::{keyed, Keyed, KeyedList};
+pub use layout::{Layout, LayoutLayer};
+pub use mutation::Mutation;
+pub use negotiate::{negotiate, Negotiate};
+pub use page::{page, Page};
+pub use response::{html, json, navigate, ResponseExt, SwapMode};
+pub use route::{Route, TypedRouterExt};
+pub use router::{RouterExt, SilcrowLayer};
+pub use schema::{Bindable, BindingSchema};
+pub use select::{AppError, Rejection, Responses};
+pub use stream::{csv, csv_stream, ndjson, ndjson_stream};
+pub use template::{with_block, Template};
+pub use upload::{MemorySink, TempDirSink, UploadLimits, Uploads};
+pub use validate::{Validate, ValidationErrors};
+pub use version::{reload, RuntimeVersion, VersionCheckLayer};
 
 // Re-export Axum primitives they might need for convenience
 pub use axum;
@@ -0,0 +1,122 @@
+// ./crates/pilcrow/src/maud.rs
+
+//! Maud integration, enabled with the `maud` feature.
+//!
+//! Maud can't splice whole attributes, so the helpers here produce attribute
+//! *values*: `p s-bind=(s_bind("user.name")) {}`. Paths are checked against
+//! the runtime's syntax in debug builds.
+
+use ::maud::{Markup, PreEscaped, Render};
+
+use crate::assets;
+use crate::render::{is_valid_path, parse_bind};
+use crate::response::{html, HtmlResponse};
+
+// ════════════════════════════════════════════════════════════
+// 1. Responses
+// ════════════════════════════════════════════════════════════
+
+impl From<Markup> for HtmlResponse {
+    fn from(markup: Markup) -> Self {
+        html(markup.into_string())
+    }
+}
+
+// ════════════════════════════════════════════════════════════
+// 2. Attribute Values
+// ════════════════════════════════════════════════════════════
+
+/// Value of an `s-bind` attribute; see [`s_bind`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Bind {
+    path: String,
+    prop: Option<String>,
+}
+
+impl Bind {
+    /// Targets a property instead of the text content (`path:prop`).
+    pub fn prop(mut self, prop: impl Into<String>) -> Self {
+        let prop = prop.into();
+        debug_assert!(
+            !prop.to_ascii_lowercase().starts_with("on"),
+            "s-bind cannot target event handler `{prop}`"
+        );
+        self.prop = Some(prop);
+        self
+    }
+}
+
+impl Render for Bind {
+    fn render_to(&self, buffer: &mut String) {
+        match &self.prop {
+            Some(prop) => format!("{}:{prop}", self.path).render_to(buffer),
+            None => self.path.render_to(buffer),
+        }
+    }
+}
+
+/// Value of an `s-list`, `s-action` or `s-target` attribute.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AttrValue(String);
+
+impl Render for AttrValue {
+    fn render_to(&self, buffer: &mut String) {
+        self.0.render_to(buffer);
+    }
+}
+
+/// Binds an element to a data path; `.field` paths bind list item fields.
+/// A `path:prop` string is split as the runtime would.
+pub fn s_bind(path: impl Into<String>) -> Bind {
+    let raw = path.into();
+    let (path, prop) = parse_bind(&raw);
+    debug_assert!(is_valid_path(path), "invalid s-bind path `{path}`");
+
+    let bind = Bind {
+        path: path.to_string(),
+        prop: None,
+    };
+    match prop {
+        Some(prop) => bind.prop(prop),
+        None => bind,
+    }
+}
+
+/// Marks a container as the list for the array at `path`.
+pub fn s_list(path: impl Into<String>) -> AttrValue {
+    let path = path.into();
+    debug_assert!(
+        is_valid_path(&path) && !path.starts_with('.'),
+        "invalid s-list path `{path}`"
+    );
+    AttrValue(path)
+}
+
+/// URL the element navigates or submits to.
+pub fn s_action(url: impl Into<String>) -> AttrValue {
+    AttrValue(url.into())
+}
+
+/// CSS selector of the element the response is swapped or patched into.
+pub fn s_target(selector: impl Into<String>) -> AttrValue {
+    let selector = selector.into();
+    debug_assert!(!selector.trim().is_empty(), "s-target selector is empty");
+    AttrValue(selector)
+}
+
+// ════════════════════════════════════════════════════════════
+// 3. Components
+// ════════════════════════════════════════════════════════════
+
+/// The `<script>` tag loading silcrow.js, for use in layouts: `(script())`.
+/// [`SilcrowLayer`](crate::SilcrowLayer) sets its app version.
+pub fn script() -> Markup {
+    PreEscaped(assets::script_tag())
+}
+
+/// Renders a configured tag: `(ScriptTag::new().nonce(nonce))`.
+impl Render for assets::ScriptTag {
+    fn render(&self) -> Markup {
+        PreEscaped(self.to_string())
+    }
+}
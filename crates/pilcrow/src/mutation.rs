@@ -0,0 +1,105 @@
+// ./crates/pilcrow/src/mutation.rs
+
+use axum::http::{header, HeaderValue, StatusCode};
+use axum::response::{IntoResponse, Response};
+use serde::Serialize;
+
+use crate::extract::{RequestMode, SilcrowRequest};
+use crate::layout::LayoutChoice;
+use crate::response::{BaseResponse, HtmlResponse, JsonResponse, NavigateResponse, ResponseExt};
+
+type RenderFragment<T> = Box<dyn FnOnce(&T) -> String + Send>;
+
+/// The answer to a successful create/update, in Post/Redirect/Get style.
+///
+/// - JSON clients (silcrow.js or API) get the value itself.
+/// - silcrow.js HTML swaps get the [`fragment`](Mutation::fragment), when one is given.
+/// - Everyone else, plain browser form posts included, gets a 303 to `redirect`.
+///
+/// Toasts ride along in each case: inline in JSON, as the toast cookie otherwise,
+/// which survives the redirect and is shown by the page it lands on.
+pub struct Mutation<T> {
+    mode: RequestMode,
+    is_silcrow: bool,
+    value: T,
+    redirect: String,
+    fragment: Option<RenderFragment<T>>,
+    created: bool,
+    base: BaseResponse,
+}
+
+impl SilcrowRequest {
+    /// Starts a [`Mutation`] response for `value`, redirecting browsers to `redirect`.
+    pub fn mutation<T>(&self, value: T, redirect: impl Into<String>) -> Mutation<T> {
+        Mutation {
+            mode: self.preferred_mode(),
+            is_silcrow: self.is_silcrow,
+            value,
+            redirect: redirect.into(),
+            fragment: None,
+            created: false,
+            base: BaseResponse::default(),
+        }
+    }
+}
+
+impl<T> Mutation<T> {
+    /// Markup silcrow.js swaps into the submitting form's target instead of following the redirect.
+    pub fn fragment<F, H>(mut self, render: F) -> Self
+    where
+        F: FnOnce(&T) -> H + Send + 'static,
+        H: Into<String>,
+    {
+        self.fragment = Some(Box::new(move |value| render(value).into()));
+        self
+    }
+
+    /// Marks the value as newly created: JSON and fragment answers become
+    /// 201 with `Location` set to the redirect target.
+    pub fn created(mut self) -> Self {
+        self.created = true;
+        self
+    }
+}
+
+impl<T> ResponseExt for Mutation<T> {
+    fn base_mut(&mut self) -> &mut BaseResponse {
+        &mut self.base
+    }
+}
+
+impl<T: Serialize> IntoResponse for Mutation<T> {
+    fn into_response(self) -> Response {
+        let mut response = match (self.mode, self.fragment) {
+            (RequestMode::Json, _) => JsonResponse {
+                data: self.value,
+                base: self.base,
+                previous: None,
+                versioned: false,
+            }
+            .into_response(),
+            (RequestMode::Html, Some(render)) if self.is_silcrow => HtmlResponse {
+                data: render(&self.value),
+                base: self.base,
+                swaps: Vec::new(),
+                layout: LayoutChoice::Inherit,
+            }
+            .into_response(),
+            _ => {
+                return NavigateResponse {
+                    path: self.redirect,
+                    base: self.base,
+                }
+                .into_response()
+            }
+        };
+
+        if self.created {
+            *response.status_mut() = StatusCode::CREATED;
+            if let Ok(location) = HeaderValue::from_str(&self.redirect) {
+                response.headers_mut().insert(header::LOCATION, location);
+            }
+        }
+        response
+    }
+}
@@ -0,0 +1,186 @@
+// ./crates/pilcrow/src/negotiate.rs
+
+use std::future::Future;
+use std::marker::PhantomData;
+use std::pin::Pin;
+
+use axum::extract::Request;
+use axum::handler::Handler;
+use axum::http::StatusCode;
+use axum::response::{IntoResponse, Response};
+
+use crate::extract::{RequestMode, SilcrowRequest};
+use crate::router::add_vary;
+use crate::select::Rejection;
+
+/// Headers every negotiated response depends on.
+const VARY: &[&str] = &["Accept", "silcrow-target"];
+
+/// A type-erased handler; axum handlers are `Clone + Send` but not `Sync`.
+trait ErasedHandler<S>: Send {
+    fn clone_box(&self) -> BoxedHandler<S>;
+    fn call(self: Box<Self>, req: Request, state: S) -> ResponseFuture;
+}
+
+type BoxedHandler<S> = Box<dyn ErasedHandler<S>>;
+type ResponseFuture = Pin<Box<dyn Future<Output = Response> + Send>>;
+
+struct Erased<H, T> {
+    handler: H,
+    _marker: PhantomData<fn() -> T>,
+}
+
+impl<H, T, S> ErasedHandler<S> for Erased<H, T>
+where
+    H: Handler<T, S>,
+    T: 'static,
+    S: 'static,
+{
+    fn clone_box(&self) -> BoxedHandler<S> {
+        boxed(self.handler.clone())
+    }
+
+    fn call(self: Box<Self>, req: Request, state: S) -> ResponseFuture {
+        Box::pin(self.handler.call(req, state))
+    }
+}
+
+fn boxed<H, T, S>(handler: H) -> BoxedHandler<S>
+where
+    H: Handler<T, S>,
+    T: 'static,
+    S: 'static,
+{
+    Box::new(Erased {
+        handler,
+        _marker: PhantomData,
+    })
+}
+
+/// One full axum handler per representation, picked by the negotiated
+/// [`RequestMode`]; the routing counterpart of [`SilcrowRequest::select`].
+///
+/// ```ignore
+/// Router::new().route("/orders", get(negotiate().html(orders_page).json(orders_api)))
+/// ```
+///
+/// Each handler keeps its own extractors. Modes without a handler answer a
+/// negotiated 406 Not Acceptable [`Rejection`].
+pub struct Negotiate<S> {
+    html: Option<BoxedHandler<S>>,
+    json: Option<BoxedHandler<S>>,
+    navigate: Option<BoxedHandler<S>>,
+    custom: Vec<(&'static str, BoxedHandler<S>)>,
+}
+
+/// Starts an empty [`Negotiate`] router.
+pub fn negotiate<S>() -> Negotiate<S> {
+    Negotiate {
+        html: None,
+        json: None,
+        navigate: None,
+        custom: Vec::new(),
+    }
+}
+
+impl<S> Clone for Negotiate<S> {
+    fn clone(&self) -> Self {
+        Negotiate {
+            html: self.html.as_ref().map(|h| h.clone_box()),
+            json: self.json.as_ref().map(|h| h.clone_box()),
+            navigate: self.navigate.as_ref().map(|h| h.clone_box()),
+            custom: self
+                .custom
+                .iter()
+                .map(|(media, h)| (*media, h.clone_box()))
+                .collect(),
+        }
+    }
+}
+
+impl<S: 'static> Negotiate<S> {
+    pub fn html<H, T>(mut self, handler: H) -> Self
+    where
+        H: Handler<T, S>,
+        T: 'static,
+    {
+        self.html = Some(boxed(handler));
+        self
+    }
+
+    pub fn json<H, T>(mut self, handler: H) -> Self
+    where
+        H: Handler<T, S>,
+        T: 'static,
+    {
+        self.json = Some(boxed(handler));
+        self
+    }
+
+    pub fn navigate<H, T>(mut self, handler: H) -> Self
+    where
+        H: Handler<T, S>,
+        T: 'static,
+    {
+        self.navigate = Some(boxed(handler));
+        self
+    }
+
+    /// Registers a handler for another media type; see
+    /// [`Responses::media`](crate::Responses::media).
+    pub fn media<H, T>(mut self, media_type: &'static str, handler: H) -> Self
+    where
+        H: Handler<T, S>,
+        T: 'static,
+    {
+        self.custom.push((media_type, boxed(handler)));
+        self
+    }
+}
+
+impl<S> Handler<(), S> for Negotiate<S>
+where
+    S: Send + Sync + 'static,
+{
+    type Future = ResponseFuture;
+
+    fn call(mut self, req: Request, state: S) -> Self::Future {
+        let silcrow = SilcrowRequest::from_headers(req.headers());
+
+        // Registered media types are weighed first; only `Accept` knows about them
+        let custom: Vec<&'static str> = self.custom.iter().map(|(media, _)| *media).collect();
+        let handler = match silcrow.mode_among(&custom) {
+            RequestMode::Other(media) => self
+                .custom
+                .iter()
+                .position(|(m, _)| *m == media)
+                .map(|index| self.custom.swap_remove(index).1)
+                .ok_or("Representation not provided"),
+            // Honor a mode another layer already settled on
+            _ => match req
+                .extensions()
+                .get::<RequestMode>()
+                .copied()
+                .unwrap_or_else(|| silcrow.preferred_mode())
+            {
+                RequestMode::Html => self.html.ok_or("HTML representation not provided"),
+                RequestMode::Json => self.json.ok_or("JSON representation not provided"),
+                RequestMode::Navigate => self.navigate.ok_or("Navigation rule not provided"),
+                RequestMode::Other(_) => Err("Representation not provided"),
+            },
+        };
+
+        let future = match handler {
+            Ok(handler) => handler.call(req, state),
+            Err(missing) => {
+                let rejection = Rejection::new(StatusCode::NOT_ACCEPTABLE, missing, &silcrow);
+                Box::pin(async move { rejection.into_response() })
+            }
+        };
+        Box::pin(async move {
+            let mut response = future.await;
+            add_vary(response.headers_mut(), VARY);
+            response
+        })
+    }
+}
@@ -0,0 +1,235 @@
+// ./crates/pilcrow/src/page.rs
+
+use axum::http::HeaderValue;
+use axum::response::{IntoResponse, Response};
+use serde::Serialize;
+
+use crate::dom::{self, Node};
+use crate::layout::{Layout, LayoutChoice};
+use crate::response::{escape_attr, BaseResponse, HtmlResponse, ResponseExt};
+
+/// Marks head nodes a page owns, so silcrow.js can replace them on the next navigation.
+const HEAD_MARKER: &str = "data-silcrow-head";
+
+// ════════════════════════════════════════════════════════════
+// 1. The Page Builder
+// ════════════════════════════════════════════════════════════
+
+/// A full page: body plus the `<head>` data that belongs to it.
+///
+/// Hard loads get a complete document (or the body wrapped by a
+/// [`LayoutLayer`](crate::LayoutLayer), with the head merged into the
+/// layout's `<head>`). Silcrow requests get the bare body and a
+/// `silcrow-head` header the runtime applies to `document.head`.
+///
+/// The standalone document doesn't load silcrow.js itself; the configured
+/// [`ScriptTag`](crate::ScriptTag) is added by [`SilcrowLayer`](crate::SilcrowLayer).
+pub struct Page {
+    pub title: Option<String>,
+    pub meta: Vec<MetaTag>,
+    pub canonical: Option<String>,
+    pub head: Vec<String>,
+    pub body: String,
+    pub base: BaseResponse,
+    pub layout: LayoutChoice,
+}
+
+/// A `<meta>` tag keyed by `name` or, for Open Graph tags, `property`.
+#[derive(Debug, Clone)]
+pub struct MetaTag {
+    pub attribute: &'static str,
+    pub key: String,
+    pub content: String,
+}
+
+pub fn page(body: impl Into<String>) -> Page {
+    Page {
+        title: None,
+        meta: Vec::new(),
+        canonical: None,
+        head: Vec::new(),
+        body: body.into(),
+        base: BaseResponse::default(),
+        layout: LayoutChoice::Inherit,
+    }
+}
+
+impl Page {
+    pub fn title(mut self, title: impl Into<String>) -> Self {
+        self.title = Some(title.into());
+        self
+    }
+
+    /// Adds `<meta name="..." content="...">`.
+    pub fn meta(mut self, name: impl Into<String>, content: impl Into<String>) -> Self {
+        self.meta.push(MetaTag {
+            attribute: "name",
+            key: name.into(),
+            content: content.into(),
+        });
+        self
+    }
+
+    /// Adds `<meta property="..." content="...">` (`og:title`, ...).
+    pub fn property(mut self, property: impl Into<String>, content: impl Into<String>) -> Self {
+        self.meta.push(MetaTag {
+            attribute: "property",
+            key: property.into(),
+            content: content.into(),
+        });
+        self
+    }
+
+    pub fn canonical(mut self, url: impl Into<String>) -> Self {
+        self.canonical = Some(url.into());
+        self
+    }
+
+    /// Adds raw markup to the head (`<link>`, `<style>`, JSON-LD...).
+    pub fn head(mut self, markup: impl Into<String>) -> Self {
+        self.head.push(markup.into());
+        self
+    }
+
+    pub fn with_layout(mut self, layout: Layout) -> Self {
+        self.layout = LayoutChoice::Use(layout);
+        self
+    }
+
+    pub fn no_layout(mut self) -> Self {
+        self.layout = LayoutChoice::Bare;
+        self
+    }
+
+    /// The page-owned head nodes, each marked for replacement by the runtime.
+    fn head_html(&self) -> String {
+        let mut out = String::new();
+        for tag in &self.meta {
+            out.push_str(&format!(
+                "<meta {}=\"{}\" content=\"{}\" {HEAD_MARKER}>",
+                tag.attribute,
+                escape_attr(&tag.key),
+                escape_attr(&tag.content)
+            ));
+        }
+        if let Some(url) = &self.canonical {
+            out.push_str(&format!(
+                "<link rel=\"canonical\" href=\"{}\" {HEAD_MARKER}>",
+                escape_attr(url)
+            ));
+        }
+        for markup in &self.head {
+            let mut nodes = dom::parse(markup);
+            for node in &mut nodes {
+                if let Node::Element(el) = node {
+                    el.set_attr(HEAD_MARKER, None);
+                }
+            }
+            out.push_str(&dom::serialize(&nodes));
+        }
+        out
+    }
+}
+
+impl ResponseExt for Page {
+    fn base_mut(&mut self) -> &mut BaseResponse {
+        &mut self.base
+    }
+}
+
+// ════════════════════════════════════════════════════════════
+// 2. Transport
+// ════════════════════════════════════════════════════════════
+
+/// Head data carried from a [`Page`] to the layout middleware.
+#[derive(Debug, Clone)]
+pub(crate) struct PageHead {
+    pub title: Option<String>,
+    pub html: String,
+    /// The bare body while the response still holds the standalone document.
+    pub body: Option<String>,
+}
+
+impl PageHead {
+    fn title_html(&self) -> String {
+        self.title
+            .as_deref()
+            .map(|title| format!("<title>{}</title>", dom::escape_text(title)))
+            .unwrap_or_default()
+    }
+
+    /// Merges the head into a layout's document, replacing its `<title>`.
+    /// Returns `false` when the document has no `</head>` yet.
+    pub fn inject(&self, document: &mut String) -> bool {
+        let Some(end) = find_ignore_case(document, "</head>") else {
+            return false;
+        };
+        let mut markup = self.html.clone();
+        if self.title.is_some() {
+            markup.insert_str(0, &self.title_html());
+        }
+        document.insert_str(end, &markup);
+
+        if self.title.is_some() {
+            // Drop the layout's own title, which now precedes ours
+            if let Some(start) = find_ignore_case(document, "<title") {
+                if start < end {
+                    if let Some(close) = find_ignore_case(&document[start..], "</title>") {
+                        document.replace_range(start..start + close + "</title>".len(), "");
+                    }
+                }
+            }
+        }
+        true
+    }
+}
+
+fn find_ignore_case(haystack: &str, needle: &str) -> Option<usize> {
+    haystack.to_ascii_lowercase().find(needle)
+}
+
+#[derive(Serialize)]
+struct HeadHeader<'a> {
+    title: Option<&'a str>,
+    html: &'a str,
+}
+
+impl IntoResponse for Page {
+    fn into_response(self) -> Response {
+        let head = PageHead {
+            title: self.title.clone(),
+            html: self.head_html(),
+            body: Some(self.body.clone()),
+        };
+
+        // Standalone document, used as-is when no layout middleware rewrites it.
+        // The script tag is left to `SilcrowLayer`, which knows the app's configured one.
+        let document = format!(
+            "<!DOCTYPE html><html><head><meta charset=\"utf-8\">{}{}</head><body>{}</body></html>",
+            head.title_html(),
+            head.html,
+            self.body
+        );
+
+        let header = serde_json::to_string(&HeadHeader {
+            title: head.title.as_deref(),
+            html: &head.html,
+        })
+        .ok()
+        .and_then(|json| HeaderValue::from_str(&urlencoding::encode(&json)).ok());
+
+        let mut response = HtmlResponse {
+            data: document,
+            base: self.base,
+            swaps: Vec::new(),
+            layout: self.layout,
+        }
+        .into_response();
+
+        if let Some(header) = header {
+            response.headers_mut().insert("silcrow-head", header);
+        }
+        response.extensions_mut().insert(head);
+        response
+    }
+}
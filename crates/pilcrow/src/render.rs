@@ -0,0 +1,463 @@
+// ./crates/pilcrow/src/render.rs
+
+use std::collections::{HashMap, HashSet};
+
+use serde::Serialize;
+use serde_json::{Map, Number, Value};
+
+use crate::dom::{self, Element, Node};
+use crate::select::AppError;
+
+// ════════════════════════════════════════════════════════════
+// 1. Public Entry Points
+// ════════════════════════════════════════════════════════════
+
+/// Pre-populates `s-bind` and `s-list` markup with `data`.
+///
+/// The output is the DOM silcrow.js would produce after calling
+/// `Silcrow.patch(data, document.body)` on the same markup: scalar bindings
+/// are filled in first, then every `s-list` is reconciled by `key` using its
+/// `s-template`, so the runtime adopts the server-rendered items on its
+/// next patch instead of re-creating them.
+///
+/// Values are coerced the way the DOM does on assignment:
+///
+/// ```
+/// use pilcrow::render::render;
+/// use serde_json::json;
+///
+/// let select = r#"<select s-bind="size:selectedIndex"><option>S</option><option>M</option></select>"#;
+/// // `null` resets the selection to the first option
+/// assert_eq!(
+///     render(select, &json!({ "size": null })),
+///     r#"<select s-bind="size:selectedIndex"><option selected>S</option><option>M</option></select>"#,
+/// );
+/// // Strings, booleans and floats go through `ToNumber`, then truncate
+/// assert_eq!(
+///     render(select, &json!({ "size": "1" })),
+///     render(select, &json!({ "size": 1.9 })),
+/// );
+///
+/// // Numbers are printed like `String(n)`
+/// let total = r#"<span s-bind="total"></span>"#;
+/// assert_eq!(render(total, &json!({ "total": 1e21 })), r#"<span s-bind="total">1e+21</span>"#);
+/// assert_eq!(render(total, &json!({ "total": 1.5e-7 })), r#"<span s-bind="total">1.5e-7</span>"#);
+/// assert_eq!(render(total, &json!({ "total": 0.25 })), r#"<span s-bind="total">0.25</span>"#);
+/// ```
+pub fn render(html: &str, data: &Value) -> String {
+    let mut nodes = dom::parse(html);
+    let templates = collect_templates(&nodes);
+
+    dom::walk_mut(&mut nodes, &mut |el| apply_binding(el, data));
+    apply_collections(&mut nodes, data, &templates);
+
+    dom::serialize(&nodes)
+}
+
+/// Serializes `data` and renders it into `html`; see [`render`].
+pub fn render_with<T: Serialize>(html: &str, data: &T) -> Result<String, AppError> {
+    let value = serde_json::to_value(data)?;
+    Ok(render(html, &value))
+}
+
+// ════════════════════════════════════════════════════════════
+// 2. Binding Syntax (mirrors silcrow.js)
+// ════════════════════════════════════════════════════════════
+
+/// Splits an `s-bind` value into its data path and optional target property.
+pub(crate) fn parse_bind(raw: &str) -> (&str, Option<&str>) {
+    match raw.split_once(':') {
+        Some((path, prop)) => (path, Some(prop)),
+        None => (raw, None),
+    }
+}
+
+/// Equivalent of the runtime's `PATH_RE`: dot-separated `[A-Za-z0-9_-]+`
+/// segments with an optional leading dot for item-local paths.
+pub(crate) fn is_valid_path(path: &str) -> bool {
+    let path = path.strip_prefix('.').unwrap_or(path);
+    !path.is_empty()
+        && path.split('.').all(|segment| {
+            !segment.is_empty()
+                && segment
+                    .chars()
+                    .all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '-')
+        })
+}
+
+fn is_on_handler(prop: &str) -> bool {
+    prop.to_ascii_lowercase().starts_with("on")
+}
+
+/// Resolves a dotted path; `None` stands in for JavaScript's `undefined`.
+pub(crate) fn resolve_path<'a>(data: &'a Value, path: &str) -> Option<&'a Value> {
+    let mut current = data;
+    for part in path.split('.') {
+        current = match current {
+            Value::Object(map) => map.get(part)?,
+            Value::Array(items) => items.get(part.parse::<usize>().ok()?)?,
+            _ => return None,
+        };
+    }
+    Some(current)
+}
+
+// ════════════════════════════════════════════════════════════
+// 3. Scalar Bindings
+// ════════════════════════════════════════════════════════════
+
+enum PropKind {
+    Str,
+    Bool,
+    Index,
+}
+
+fn known_prop(prop: &str) -> Option<PropKind> {
+    match prop {
+        "value" | "src" | "href" => Some(PropKind::Str),
+        "checked" | "disabled" | "selected" => Some(PropKind::Bool),
+        "selectedIndex" => Some(PropKind::Index),
+        _ => None,
+    }
+}
+
+fn apply_binding(el: &mut Element, data: &Value) {
+    let Some(raw) = el.attr("s-bind").map(str::to_owned) else {
+        return;
+    };
+    let (path, prop) = parse_bind(&raw);
+    if path.is_empty() || path.starts_with('.') || !is_valid_path(path) {
+        return;
+    }
+    if let Some(value) = resolve_path(data, path) {
+        set_value(el, prop, value);
+    }
+}
+
+/// Server-side `setValue`: properties the runtime assigns through the DOM are
+/// reflected into the attributes that produce the same initial state.
+fn set_value(el: &mut Element, prop: Option<&str>, value: &Value) {
+    let Some(prop) = prop else {
+        el.set_text(&js_string(value));
+        return;
+    };
+    if is_on_handler(prop) {
+        return;
+    }
+
+    match (known_prop(prop), value) {
+        (Some(PropKind::Bool), value) => {
+            if truthy(value) {
+                el.set_attr(prop, None);
+            } else {
+                el.remove_attr(prop);
+            }
+        }
+        // `el.selectedIndex = null` selects the first option, like `0`
+        (Some(PropKind::Index), value) => select_index(el, js_int32(value)),
+        (Some(PropKind::Str), Value::Null) => set_string_prop(el, prop, ""),
+        (Some(PropKind::Str), value) => set_string_prop(el, prop, &js_string(value)),
+        (None, Value::Null) => el.remove_attr(&prop.to_ascii_lowercase()),
+        (None, value) => el.set_attr(&prop.to_ascii_lowercase(), Some(js_string(value))),
+    }
+}
+
+fn set_string_prop(el: &mut Element, prop: &str, value: &str) {
+    if prop == "value" && el.name == "textarea" {
+        el.set_text(value);
+    } else {
+        el.set_attr(prop, Some(value.to_string()));
+    }
+}
+
+/// Out-of-range indexes leave no option selected, as with `-1`.
+fn select_index(el: &mut Element, index: i32) {
+    if el.name != "select" {
+        return;
+    }
+    let mut position = 0;
+    dom::walk_mut(&mut el.children, &mut |option| {
+        if option.name != "option" {
+            return;
+        }
+        if position == index {
+            option.set_attr("selected", None);
+        } else {
+            option.remove_attr("selected");
+        }
+        position += 1;
+    });
+}
+
+/// JavaScript truthiness for JSON values.
+fn truthy(value: &Value) -> bool {
+    match value {
+        Value::Null => false,
+        Value::Bool(b) => *b,
+        Value::Number(n) => n.as_f64().is_some_and(|f| f != 0.0),
+        Value::String(s) => !s.is_empty(),
+        Value::Array(_) | Value::Object(_) => true,
+    }
+}
+
+/// `ToInt32(ToNumber(value))`, the conversion behind assigning a DOM `long`
+/// property such as `selectedIndex`.
+fn js_int32(value: &Value) -> i32 {
+    let number = match value {
+        Value::Null => 0.0,
+        Value::Bool(b) => f64::from(u8::from(*b)),
+        Value::Number(n) => n.as_f64().unwrap_or(0.0),
+        Value::String(s) => string_to_number(s),
+        Value::Array(_) => string_to_number(&js_string(value)),
+        Value::Object(_) => f64::NAN,
+    };
+    if !number.is_finite() {
+        return 0;
+    }
+    // Wraps modulo 2^32 like the spec; `as i64` alone saturates
+    number.trunc().rem_euclid(4_294_967_296.0) as u32 as i32
+}
+
+/// `ToNumber` on a string: blank is `0`, anything unparsable `NaN`.
+fn string_to_number(s: &str) -> f64 {
+    let s = s.trim();
+    if s.is_empty() {
+        return 0.0;
+    }
+    let radix = [("0x", 16), ("0X", 16), ("0o", 8), ("0O", 8), ("0b", 2), ("0B", 2)]
+        .into_iter()
+        .find_map(|(prefix, radix)| Some((s.strip_prefix(prefix)?, radix)));
+    if let Some((digits, radix)) = radix {
+        return u64::from_str_radix(digits, radix).map_or(f64::NAN, |n| n as f64);
+    }
+    match s.trim_start_matches(['+', '-']) {
+        "Infinity" => return if s.starts_with('-') { f64::NEG_INFINITY } else { f64::INFINITY },
+        // Rust also accepts `inf` and `nan`, JavaScript doesn't
+        rest if rest.starts_with(|c: char| c.is_ascii_alphabetic()) => return f64::NAN,
+        _ => {}
+    }
+    s.parse().unwrap_or(f64::NAN)
+}
+
+/// `String(value)` as the runtime would compute it (`null` renders empty,
+/// as it does for `textContent` and inside arrays).
+pub(crate) fn js_string(value: &Value) -> String {
+    match value {
+        Value::Null => String::new(),
+        Value::Bool(b) => b.to_string(),
+        Value::Number(n) => js_number(n),
+        Value::String(s) => s.clone(),
+        Value::Array(items) => items.iter().map(js_string).collect::<Vec<_>>().join(","),
+        Value::Object(_) => "[object Object]".to_string(),
+    }
+}
+
+/// `Number::toString`: JSON numbers are doubles in the browser, printed as
+/// the shortest round-tripping digits, in exponent form outside `[1e-6, 1e21)`.
+fn js_number(n: &Number) -> String {
+    let f = n.as_f64().unwrap_or(0.0);
+    if f == 0.0 {
+        return "0".to_string();
+    }
+    if (1e-6..1e21).contains(&f.abs()) {
+        return f.to_string();
+    }
+    // Rust writes `1e21` and `1.5e-7`; JavaScript signs positive exponents
+    let exp = format!("{f:e}");
+    match exp.split_once('e') {
+        Some((mantissa, exponent)) if !exponent.starts_with('-') => format!("{mantissa}e+{exponent}"),
+        _ => exp,
+    }
+}
+
+// ════════════════════════════════════════════════════════════
+// 4. Keyed Collections
+// ════════════════════════════════════════════════════════════
+
+fn collect_templates(nodes: &[Node]) -> HashMap<String, Element> {
+    let mut templates = HashMap::new();
+    dom::walk(nodes, &mut |el| {
+        if el.name == "template" {
+            if let Some(id) = el.attr("id") {
+                templates.entry(id.to_string()).or_insert_with(|| el.clone());
+            }
+        }
+    });
+    templates
+}
+
+/// Reconciles containers bottom-up so freshly cloned items are never
+/// re-scanned, just as the runtime only knows the lists present at patch time.
+fn apply_collections(nodes: &mut [Node], data: &Value, templates: &HashMap<String, Element>) {
+    for node in nodes {
+        let Node::Element(el) = node else { continue };
+        if el.name == "template" {
+            continue;
+        }
+        apply_collections(&mut el.children, data, templates);
+
+        let Some(name) = el.attr("s-list").map(str::to_owned) else {
+            continue;
+        };
+        if !is_valid_path(&name) {
+            continue;
+        }
+        if let Some(Value::Array(items)) = resolve_path(data, &name) {
+            reconcile(el, items, templates);
+        }
+    }
+}
+
+fn reconcile(container: &mut Element, items: &[Value], templates: &HashMap<String, Element>) {
+    // isValidCollectionArray: every item must be an object carrying `key`
+    let mut objects = Vec::with_capacity(items.len());
+    for item in items {
+        match item {
+            Value::Object(map) if map.contains_key("key") => objects.push(map),
+            _ => return,
+        }
+    }
+    let valid: Vec<&Map<String, Value>> = objects
+        .into_iter()
+        .filter(|item| !item["key"].is_null())
+        .collect();
+
+    let mut seen = HashSet::new();
+    if !valid.iter().all(|item| seen.insert(js_string(&item["key"]))) {
+        return;
+    }
+
+    // Children are tracked by identity so moves mirror `prepend`/`after`
+    let mut slots: Vec<(usize, Node)> = std::mem::take(&mut container.children)
+        .into_iter()
+        .enumerate()
+        .collect();
+    let mut next_id = slots.len();
+
+    let mut existing: HashMap<String, usize> = HashMap::new();
+    let mut unkeyed = Vec::new();
+    for (id, node) in &slots {
+        let Some(el) = node.as_element().filter(|el| el.has_attr("s-key")) else {
+            continue;
+        };
+        match el.attr("data-key") {
+            Some(key) => {
+                existing.insert(key.to_string(), *id);
+            }
+            None => unkeyed.push(*id),
+        }
+    }
+
+    let position = |slots: &[(usize, Node)], id: usize| slots.iter().position(|(i, _)| *i == id);
+    let mut next_keys = HashSet::new();
+    let mut prev: Option<usize> = None;
+
+    for item in valid {
+        let key = js_string(&item["key"]);
+        next_keys.insert(key.clone());
+
+        let existing_id = existing
+            .get(&key)
+            .copied()
+            .filter(|&id| position(&slots, id).is_some());
+
+        let id = match existing_id {
+            Some(id) => {
+                if let Some(el) = position(&slots, id).and_then(|pos| slots[pos].1.as_element_mut()) {
+                    patch_item(el, item);
+                }
+
+                // Only move nodes that are not already the next element in line
+                let start = prev.and_then(|p| position(&slots, p)).map_or(0, |p| p + 1);
+                let in_place = slots[start..]
+                    .iter()
+                    .find(|(_, node)| node.as_element().is_some())
+                    .is_some_and(|(i, _)| *i == id);
+                if !in_place {
+                    if let Some(pos) = position(&slots, id) {
+                        let moved = slots.remove(pos);
+                        let start = if pos < start { start - 1 } else { start };
+                        slots.insert(start, moved);
+                    }
+                }
+                id
+            }
+            None => {
+                let mut el = resolve_template(container, &slots, item, templates);
+                el.set_attr("data-key", Some(key));
+                el.set_attr("s-key", Some(String::new()));
+                patch_item(&mut el, item);
+
+                let start = prev.and_then(|p| position(&slots, p)).map_or(0, |p| p + 1);
+                let id = next_id;
+                next_id += 1;
+                slots.insert(start, (id, Node::Element(el)));
+                id
+            }
+        };
+        prev = Some(id);
+    }
+
+    let stale: HashSet<usize> = existing
+        .iter()
+        .filter(|(key, _)| !next_keys.contains(*key))
+        .map(|(_, id)| *id)
+        .chain(unkeyed)
+        .collect();
+
+    container.children = slots
+        .into_iter()
+        .filter(|(id, _)| !stale.contains(id))
+        .map(|(_, node)| node)
+        .collect();
+}
+
+/// Mirrors `makeTemplateResolver`: a `tpl#id` key prefix wins, then the
+/// container's `s-template`, then its first direct `<template>` child.
+fn resolve_template(
+    container: &Element,
+    children: &[(usize, Node)],
+    item: &Map<String, Value>,
+    templates: &HashMap<String, Element>,
+) -> Element {
+    let key = js_string(&item["key"]);
+    let by_key = key
+        .split_once('#')
+        .and_then(|(name, _)| templates.get(name));
+    let by_attr = || container.attr("s-template").and_then(|id| templates.get(id));
+    let by_child = || {
+        children
+            .iter()
+            .filter_map(|(_, node)| node.as_element())
+            .find(|el| el.name == "template")
+    };
+
+    let Some(tpl) = by_key.or_else(by_attr).or_else(by_child) else {
+        return Element::new("div");
+    };
+
+    let mut roots = tpl.element_children();
+    match (roots.next(), roots.next()) {
+        (Some(root), None) => root.clone(),
+        _ => Element::new("div"),
+    }
+}
+
+/// Applies item fields to the `.field` bindings inside one list item.
+fn patch_item(node: &mut Element, item: &Map<String, Value>) {
+    dom::walk_subtree_mut(node, &mut |el| {
+        let Some(raw) = el.attr("s-bind").map(str::to_owned) else {
+            return;
+        };
+        let (path, prop) = parse_bind(&raw);
+        let Some(field) = path.strip_prefix('.') else {
+            return;
+        };
+        if field == "key" {
+            return;
+        }
+        if let Some(value) = item.get(field) {
+            set_value(el, prop, value);
+        }
+    });
+}
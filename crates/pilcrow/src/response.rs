@@ -5,6 +5,11 @@
 };
 use cookie::{Cookie, SameSite};
 use serde::{Deserialize, Serialize};
+use std::time::Duration;
+
+use crate::cache::CachePolicy;
+use crate::diff;
+use crate::layout::{Layout, LayoutChoice};
 
 // ════════════════════════════════════════════════════════════
 // 1. Shared State & Modifiers
@@ -21,6 +26,8 @@ pub struct BaseResponse {
     pub headers: HeaderMap,
     pub cookies: Vec<Cookie<'static>>,
     pub toasts: Vec<Toast>, // Future-proof: multiple toasts
+    /// Picked up by `CacheLayer` when the response is stored server-side.
+    pub cache: Option<CachePolicy>,
 }
 
 impl BaseResponse {
@@ -38,6 +45,11 @@ pub fn apply_to_response(&self, response: &mut Response) {
                 response.headers_mut().append(SET_COOKIE, header_value);
             }
         }
+
+        // 3. Hand the server-side cache policy to `CacheLayer`
+        if let Some(policy) = &self.cache {
+            response.extensions_mut().insert(policy.clone());
+        }
     }
 
     /// Safely formats toasts as URL-encoded cookies for HTML/Navigate responses.
@@ -79,6 +91,20 @@ fn no_cache(self) -> Self {
         self.with_header("silcrow-cache", "no-cache")
     }
 
+    /// Lets `CacheLayer` keep this response for `ttl`; responses without
+    /// `cache_for` or `cache_tag` are never stored server-side.
+    fn cache_for(mut self, ttl: Duration) -> Self {
+        self.base_mut().cache.get_or_insert_with(CachePolicy::default).ttl = Some(ttl);
+        self
+    }
+
+    /// Tags the server-side cache entry so `ResponseCache::invalidate_tag` can drop it.
+    fn cache_tag(mut self, tag: impl Into<String>) -> Self {
+        let policy = self.base_mut().cache.get_or_insert_with(CachePolicy::default);
+        policy.tags.push(tag.into());
+        self
+    }
+
     fn with_toast(mut self, message: impl Into<String>, level: impl Into<String>) -> Self {
         self.base_mut().toasts.push(Toast {
             message: message.into(),
@@ -96,11 +122,117 @@ fn with_toast(mut self, message: impl Into<String>, level: impl Into<String>) ->
 pub struct HtmlResponse {
     pub data: String,
     pub base: BaseResponse,
+    pub swaps: Vec<OutOfBandSwap>,
+    pub layout: LayoutChoice,
+}
+
+/// How silcrow.js applies an out-of-band fragment to its selector.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum SwapMode {
+    /// Replace the target's children (the default).
+    #[default]
+    Inner,
+    /// Replace the target element itself.
+    Outer,
+    /// Insert after the target's last child.
+    Append,
+    /// Insert before the target's first child.
+    Prepend,
+    /// Remove the target element; the markup is ignored.
+    Remove,
+}
+
+impl SwapMode {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            SwapMode::Inner => "inner",
+            SwapMode::Outer => "outer",
+            SwapMode::Append => "append",
+            SwapMode::Prepend => "prepend",
+            SwapMode::Remove => "remove",
+        }
+    }
+}
+
+/// An extra fragment shipped alongside the main HTML body.
+#[derive(Debug, Clone)]
+pub struct OutOfBandSwap {
+    pub selector: String,
+    pub mode: SwapMode,
+    pub markup: String,
+}
+
+impl OutOfBandSwap {
+    /// Encodes the fragment as an inert `<template s-swap>` element that
+    /// silcrow.js strips from the body and applies to `selector`.
+    fn render(&self) -> String {
+        let markup = match self.mode {
+            SwapMode::Remove => "",
+            _ => self.markup.as_str(),
+        };
+        format!(
+            "<template s-swap=\"{}\" s-swap-mode=\"{}\">{}</template>",
+            escape_attr(&self.selector),
+            self.mode.as_str(),
+            markup
+        )
+    }
+}
+
+pub(crate) fn escape_attr(value: &str) -> String {
+    value
+        .replace('&', "&amp;")
+        .replace('"', "&quot;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+}
+
+impl HtmlResponse {
+    /// Also replaces the children of `selector` with `markup` in the same response.
+    pub fn also_swap(self, selector: impl Into<String>, markup: impl Into<String>) -> Self {
+        self.also_swap_with(selector, SwapMode::Inner, markup)
+    }
+
+    /// Also applies `markup` to `selector` using an explicit [`SwapMode`].
+    pub fn also_swap_with(
+        mut self,
+        selector: impl Into<String>,
+        mode: SwapMode,
+        markup: impl Into<String>,
+    ) -> Self {
+        self.swaps.push(OutOfBandSwap {
+            selector: selector.into(),
+            mode,
+            markup: markup.into(),
+        });
+        self
+    }
+
+    /// Wraps this page in `layout` instead of the innermost registered one.
+    pub fn with_layout(mut self, layout: Layout) -> Self {
+        self.layout = LayoutChoice::Use(layout);
+        self
+    }
+
+    /// Sends the body as-is, even on hard loads.
+    pub fn no_layout(mut self) -> Self {
+        self.layout = LayoutChoice::Bare;
+        self
+    }
 }
 
 impl IntoResponse for HtmlResponse {
     fn into_response(self) -> Response {
-        let mut response = axum::response::Html(self.data).into_response();
+        let mut data = self.data;
+        for swap in &self.swaps {
+            data.push_str(&swap.render());
+        }
+
+        let mut response = axum::response::Html(data).into_response();
+        // Picked up by `LayoutLayer`, which knows whether this is a hard load
+        if !matches!(self.layout, LayoutChoice::Bare) {
+            response.extensions_mut().insert(self.layout);
+        }
         self.base.apply_to_response(&mut response);
         self.base.apply_toast_cookies(&mut response);
         response
@@ -111,6 +243,27 @@ fn into_response(self) -> Response {
 pub struct JsonResponse<T> {
     pub data: T,
     pub base: BaseResponse,
+    pub previous: Option<serde_json::Value>,
+    pub versioned: bool,
+}
+
+impl<T> JsonResponse<T> {
+    /// Tags the payload with a `silcrow-version` header so the runtime can ask
+    /// for a diff against it on its next request.
+    pub fn versioned(mut self) -> Self {
+        self.versioned = true;
+        self
+    }
+
+    /// Ships only the RFC 7386 merge patch from `previous` to this payload.
+    ///
+    /// The runtime applies it on top of the snapshot it already holds; prefer
+    /// [`SilcrowRequest::json_patch`](crate::SilcrowRequest::json_patch), which
+    /// only diffs when the client actually holds `previous`.
+    pub fn diff_from(mut self, previous: serde_json::Value) -> Self {
+        self.previous = Some(previous);
+        self
+    }
 }
 
 impl<T: serde::Serialize> IntoResponse for JsonResponse<T> {
@@ -121,6 +274,20 @@ fn into_response(self) -> Response {
             Err(_) => return StatusCode::INTERNAL_SERVER_ERROR.into_response(),
         };
 
+        #[cfg(debug_assertions)]
+        crate::keyed::check_payload(&json_payload);
+
+        // Versioned payloads: tag the full snapshot, then swap in the diff if requested
+        let mut patch_headers = Vec::new();
+        if self.versioned || self.previous.is_some() {
+            patch_headers.push(("silcrow-version", diff::version(&json_payload)));
+        }
+        if let Some(previous) = &self.previous {
+            patch_headers.push(("silcrow-patch", "merge".to_string()));
+            patch_headers.push(("silcrow-patch-base", diff::version(previous)));
+            json_payload = diff::merge_patch(previous, &json_payload);
+        }
+
         // Fix #2: Handle toasts safely, even if the root isn't an Object
         if !self.base.toasts.is_empty() {
             let toasts_json = serde_json::json!(self.base.toasts);
@@ -138,6 +305,11 @@ fn into_response(self) -> Response {
         }
 
         let mut response = Json(json_payload).into_response();
+        for (name, value) in patch_headers {
+            if let Ok(value) = HeaderValue::from_str(&value) {
+                response.headers_mut().insert(name, value);
+            }
+        }
         self.base.apply_to_response(&mut response); // Apply headers/cookies (but NOT toast cookies)
         response
     }
@@ -171,6 +343,8 @@ pub fn html(data: impl Into<String>) -> HtmlResponse {
     HtmlResponse {
         data: data.into(),
         base: BaseResponse::default(),
+        swaps: Vec::new(),
+        layout: LayoutChoice::Inherit,
     }
 }
 
@@ -178,6 +352,8 @@ pub fn json<T>(data: T) -> JsonResponse<T> {
     JsonResponse {
         data,
         base: BaseResponse::default(),
+        previous: None,
+        versioned: false,
     }
 }
 
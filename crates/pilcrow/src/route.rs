@@ -0,0 +1,104 @@
+// ./crates/pilcrow/src/route.rs
+
+use std::collections::HashMap;
+
+use axum::extract::{FromRequestParts, Path};
+use axum::http::request::Parts;
+use axum::routing::MethodRouter;
+use axum::Router;
+
+use crate::select::AppError;
+
+pub use pilcrow_macros::Route;
+
+/// A URL with typed parameters; derive it with `#[derive(Route)]`.
+///
+/// Paths are relative to where the router is mounted: a `navigate()` or
+/// template link in an app served under a prefix (the
+/// [`base_path`](crate::SilcrowLayer::base_path) given to `SilcrowLayer`)
+/// needs [`to_path_under`](Route::to_path_under) instead.
+///
+/// ```
+/// use std::collections::HashMap;
+/// use pilcrow::axum::{routing::get, Router};
+/// use pilcrow::{Route, TypedRouterExt};
+///
+/// #[derive(Route)]
+/// #[route("/orders/{id}")]
+/// struct OrderPage { id: u64 }
+///
+/// #[derive(Route)]
+/// #[route("/files/{*path}")]
+/// struct FilePage { path: String }
+///
+/// async fn show_order(order: OrderPage) -> String {
+///     format!("Order {}", order.id)
+/// }
+///
+/// let _app: Router = Router::new().typed_route::<OrderPage>(get(show_order));
+/// assert_eq!(OrderPage::PATH, "/orders/:id");
+/// assert_eq!(OrderPage { id: 7 }.to_path(), "/orders/7");
+/// assert_eq!(OrderPage { id: 7 }.to_path_under("/shop/"), "/shop/orders/7");
+///
+/// // Wildcards keep their slashes; everything else is percent-encoded
+/// let file = FilePage { path: "/reports/q1 draft?.pdf".into() };
+/// assert_eq!(file.to_path(), "/files/reports/q1%20draft%3F.pdf");
+///
+/// let params = HashMap::from([("id".to_string(), "7".to_string())]);
+/// assert_eq!(OrderPage::from_params(&params).map(|o| o.id), Some(7));
+/// let params = HashMap::from([("id".to_string(), "seven".to_string())]);
+/// assert!(OrderPage::from_params(&params).is_none());
+/// ```
+pub trait Route: Sized {
+    /// The pattern in axum's registration syntax.
+    const PATH: &'static str;
+
+    /// The concrete, percent-encoded URL path, relative to the mount point.
+    fn to_path(&self) -> String;
+
+    /// [`to_path`](Route::to_path) under a mount prefix such as `/app`.
+    fn to_path_under(&self, base_path: &str) -> String {
+        format!("{}{}", base_path.trim_end_matches('/'), self.to_path())
+    }
+
+    /// Parses the captured path parameters; `None` when one fails to parse.
+    fn from_params(params: &HashMap<String, String>) -> Option<Self>;
+}
+
+/// Registers a handler under a [`Route`]'s pattern.
+pub trait TypedRouterExt<S> {
+    fn typed_route<R: Route>(self, method_router: MethodRouter<S>) -> Self;
+}
+
+impl<S: Clone + Send + Sync + 'static> TypedRouterExt<S> for Router<S> {
+    fn typed_route<R: Route>(self, method_router: MethodRouter<S>) -> Self {
+        self.route(R::PATH, method_router)
+    }
+}
+
+/// Backs the derived `FromRequestParts`: a path that doesn't parse is a 404,
+/// since no such resource can exist.
+#[doc(hidden)]
+pub async fn extract<R: Route>(parts: &mut Parts) -> Result<R, AppError> {
+    let not_found = || AppError::NotFound("Not Found".into());
+    let Path(params) = Path::<HashMap<String, String>>::from_request_parts(parts, &())
+        .await
+        .map_err(|_| not_found())?;
+    R::from_params(&params).ok_or_else(not_found)
+}
+
+#[doc(hidden)]
+pub fn encode_segment(value: &str) -> String {
+    urlencoding::encode(value).into_owned()
+}
+
+/// Wildcards keep their slashes; each piece between them is encoded.
+#[doc(hidden)]
+pub fn encode_wildcard(value: &str) -> String {
+    value
+        .trim_start_matches('/')
+        .split('/')
+        .map(encode_segment)
+        .collect::<Vec<_>>()
+        .join("/")
+}
@@ -0,0 +1,247 @@
+// ./crates/pilcrow/src/router.rs
+
+use std::convert::Infallible;
+use std::future::Future;
+use std::pin::Pin;
+use std::task::{Context, Poll};
+
+use axum::body::Body;
+use axum::http::{header, HeaderMap, HeaderValue, Request};
+use axum::response::{IntoResponse, Response};
+use axum::Router;
+use tower_layer::Layer;
+use tower_service::Service;
+
+use crate::assets::{self, ScriptTag};
+use crate::extract::SilcrowRequest;
+use crate::response::escape_attr;
+use crate::version::{VersionCheckLayer, VersionCheckService};
+
+// ════════════════════════════════════════════════════════════
+// 1. Configuration
+// ════════════════════════════════════════════════════════════
+
+/// Everything an app needs to run Silcrow, configured in one place.
+///
+/// Per request it stores the negotiated [`RequestMode`](crate::extract::RequestMode) in the request
+/// extensions, answers stale runtimes with a reload directive, adds the
+/// script tag to full HTML documents that lack it and marks negotiated
+/// responses with `Vary`. A tag a layout already emitted gets the configured
+/// runtime token, so the handshake sees the app version it expects. Install it with [`RouterExt::with_silcrow`].
+#[derive(Debug, Clone)]
+pub struct SilcrowLayer {
+    script: ScriptTag,
+    inject_script: bool,
+    version_check: bool,
+}
+
+impl SilcrowLayer {
+    pub fn new() -> Self {
+        SilcrowLayer {
+            script: ScriptTag::new(),
+            inject_script: true,
+            version_check: true,
+        }
+    }
+
+    /// The tag injected into documents; its app version is also the one
+    /// the version check expects.
+    pub fn script_tag(mut self, script: ScriptTag) -> Self {
+        self.script = script;
+        self
+    }
+
+    pub fn base_path(mut self, base_path: impl Into<String>) -> Self {
+        self.script = self.script.base_path(base_path);
+        self
+    }
+
+    pub fn app_version(mut self, version: impl Into<String>) -> Self {
+        self.script = self.script.app_version(version);
+        self
+    }
+
+    /// Leave documents alone; layouts must then emit a tag with the same
+    /// app version, or every Silcrow request is answered with a reload.
+    pub fn inject_script(mut self, enabled: bool) -> Self {
+        self.inject_script = enabled;
+        self
+    }
+
+    pub fn version_check(mut self, enabled: bool) -> Self {
+        self.version_check = enabled;
+        self
+    }
+}
+
+impl Default for SilcrowLayer {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Mounts the Silcrow assets and wraps every route in a [`SilcrowLayer`].
+pub trait RouterExt {
+    fn with_silcrow(self, layer: SilcrowLayer) -> Self;
+}
+
+impl<S: Clone + Send + Sync + 'static> RouterExt for Router<S> {
+    fn with_silcrow(self, layer: SilcrowLayer) -> Self {
+        self.merge(assets::routes()).layer(layer)
+    }
+}
+
+// ════════════════════════════════════════════════════════════
+// 2. Middleware
+// ════════════════════════════════════════════════════════════
+
+impl<S> Layer<S> for SilcrowLayer {
+    type Service = SilcrowService<S>;
+
+    fn layer(&self, inner: S) -> Self::Service {
+        // Stale runtimes are answered by the same service `VersionCheckLayer` installs
+        let version_check = if self.version_check {
+            VersionCheckLayer::new(self.script.runtime().clone())
+        } else {
+            VersionCheckLayer::disabled()
+        };
+        SilcrowService {
+            inner: version_check.layer(inner),
+            script: self.inject_script.then(|| self.script.to_string()),
+            runtime: self.script.runtime().token(),
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct SilcrowService<S> {
+    inner: VersionCheckService<S>,
+    script: Option<String>,
+    runtime: String,
+}
+
+impl<S, B> Service<Request<B>> for SilcrowService<S>
+where
+    S: Service<Request<B>, Response = Response, Error = Infallible> + Clone + Send + 'static,
+    S::Future: Send + 'static,
+    B: Send + 'static,
+{
+    type Response = Response;
+    type Error = Infallible;
+    type Future = Pin<Box<dyn Future<Output = Result<Response, Infallible>> + Send>>;
+
+    fn poll_ready(&mut self, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        self.inner.poll_ready(cx)
+    }
+
+    fn call(&mut self, mut request: Request<B>) -> Self::Future {
+        let silcrow = SilcrowRequest::from_headers(request.headers());
+        request.extensions_mut().insert(silcrow.preferred_mode());
+
+        // Only hard loads render whole documents
+        let script = self.script.clone().filter(|_| !silcrow.is_silcrow);
+        let runtime = self.runtime.clone();
+
+        let clone = self.inner.clone();
+        let mut inner = std::mem::replace(&mut self.inner, clone);
+
+        Box::pin(async move {
+            let mut response = inner.call(request).await?;
+            let negotiated = content_type(response.headers())
+                .is_some_and(|ct| ct.starts_with("text/html") || ct.starts_with("application/json"));
+            if !negotiated {
+                return Ok(response);
+            }
+
+            add_vary(response.headers_mut(), &["Accept", "silcrow-target"]);
+            if let Some(script) = script {
+                response = inject_script(response, &script, &runtime).await;
+            }
+            Ok(response)
+        })
+    }
+}
+
+fn content_type(headers: &HeaderMap) -> Option<&str> {
+    headers.get(header::CONTENT_TYPE).and_then(|v| v.to_str().ok())
+}
+
+pub(crate) fn add_vary(headers: &mut HeaderMap, names: &[&str]) {
+    let mut vary: Vec<String> = headers
+        .get_all(header::VARY)
+        .iter()
+        .filter_map(|v| v.to_str().ok())
+        .flat_map(|v| v.split(','))
+        .map(|v| v.trim().to_string())
+        .filter(|v| !v.is_empty())
+        .collect();
+    for name in names {
+        if !vary.iter().any(|v| v.eq_ignore_ascii_case(name) || v == "*") {
+            vary.push(name.to_string());
+        }
+    }
+    if let Ok(value) = HeaderValue::from_str(&vary.join(", ")) {
+        headers.insert(header::VARY, value);
+    }
+}
+
+/// Adds the script tag to a full HTML document that doesn't load silcrow.js
+/// yet, or points an existing one at `runtime`.
+async fn inject_script(response: Response, script: &str, runtime: &str) -> Response {
+    let is_html = content_type(response.headers()).is_some_and(|ct| ct.starts_with("text/html"));
+    if !is_html || response.headers().contains_key(header::CONTENT_ENCODING) {
+        return response;
+    }
+
+    let (mut parts, body) = response.into_parts();
+    let Ok(bytes) = axum::body::to_bytes(body, usize::MAX).await else {
+        return axum::http::StatusCode::INTERNAL_SERVER_ERROR.into_response();
+    };
+    let Ok(mut document) = String::from_utf8(bytes.to_vec()) else {
+        return Response::from_parts(parts, Body::from(bytes));
+    };
+
+    let lower = document.to_ascii_lowercase();
+    match lower.find("/_silcrow/silcrow") {
+        Some(src) => {
+            if set_runtime(&mut document, &lower, src, runtime) {
+                parts.headers.remove(header::CONTENT_LENGTH);
+            }
+        }
+        None => {
+            let anchor = lower.find("</head>").or_else(|| lower.find("</body>"));
+            if let Some(index) = anchor {
+                document.insert_str(index, script);
+                parts.headers.remove(header::CONTENT_LENGTH);
+            }
+        }
+    }
+    Response::from_parts(parts, Body::from(document))
+}
+
+/// Rewrites the `data-runtime` of the `<script>` tag around `src`, e.g. one
+/// from [`script_tag`](crate::assets::script_tag) that doesn't know the app
+/// version. Returns whether the document changed.
+fn set_runtime(document: &mut String, lower: &str, src: usize, runtime: &str) -> bool {
+    let Some(start) = lower[..src].rfind("<script") else {
+        return false;
+    };
+    let Some(end) = lower[src..].find('>').map(|end| src + end) else {
+        return false;
+    };
+    let token = escape_attr(runtime);
+    match lower[start..end].find("data-runtime=\"") {
+        Some(attr) => {
+            let value = start + attr + "data-runtime=\"".len();
+            let Some(close) = lower[value..end].find('"').map(|close| value + close) else {
+                return false;
+            };
+            if document[value..close] == token {
+                return false;
+            }
+            document.replace_range(value..close, &token);
+        }
+        None => document.insert_str(end, &format!(" data-runtime=\"{token}\"")),
+    }
+    true
+}
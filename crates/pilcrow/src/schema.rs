@@ -0,0 +1,422 @@
+// ./crates/pilcrow/src/schema.rs
+
+use std::collections::{BTreeMap, BTreeSet, HashMap, HashSet, VecDeque};
+use std::fmt;
+use std::rc::Rc;
+use std::sync::Arc;
+
+use serde_json::Value;
+
+use crate::dom::{self, Element, Node};
+use crate::render::{is_valid_path, parse_bind};
+
+pub use pilcrow_macros::Bindable;
+
+// ════════════════════════════════════════════════════════════
+// 1. The Schema Tree
+// ════════════════════════════════════════════════════════════
+
+/// The JSON shape a payload can take, as far as `s-bind`/`s-list` care.
+#[derive(Debug, Clone, PartialEq)]
+pub enum BindingSchema {
+    /// A leaf rendered with `String(value)`.
+    Scalar,
+    /// An object with a fixed set of fields.
+    Object(BTreeMap<String, BindingSchema>),
+    /// An array whose items share one shape.
+    List(Box<BindingSchema>),
+    /// Anything may live below this point (maps, `serde_json::Value`, ...).
+    Any,
+}
+
+impl BindingSchema {
+    /// The schema of a type deriving or implementing [`Bindable`].
+    pub fn of<T: Bindable + ?Sized>() -> Self {
+        T::binding_schema()
+    }
+
+    /// An object with no fields yet.
+    pub fn object() -> Self {
+        BindingSchema::Object(BTreeMap::new())
+    }
+
+    /// Adds a field to an object schema; other shapes are left untouched.
+    pub fn insert_field(&mut self, name: &str, schema: BindingSchema) {
+        if let BindingSchema::Object(fields) = self {
+            fields.insert(name.to_string(), schema);
+        }
+    }
+
+    /// Unions two schemas (used for `#[serde(flatten)]` and sample arrays).
+    pub fn merge(self, other: BindingSchema) -> Self {
+        use BindingSchema::*;
+        match (self, other) {
+            (Object(mut a), Object(b)) => {
+                for (name, schema) in b {
+                    let merged = match a.remove(&name) {
+                        Some(existing) => existing.merge(schema),
+                        None => schema,
+                    };
+                    a.insert(name, merged);
+                }
+                Object(a)
+            }
+            (List(a), List(b)) => List(Box::new(a.merge(*b))),
+            (Scalar, Scalar) => Scalar,
+            _ => Any,
+        }
+    }
+
+    /// Infers a schema from a representative payload. `null` leaves are
+    /// treated as [`BindingSchema::Any`] since their type is unknown.
+    pub fn from_value(value: &Value) -> Self {
+        match value {
+            Value::Null => BindingSchema::Any,
+            Value::Bool(_) | Value::Number(_) | Value::String(_) => BindingSchema::Scalar,
+            Value::Array(items) => {
+                let item = items
+                    .iter()
+                    .map(BindingSchema::from_value)
+                    .reduce(BindingSchema::merge)
+                    .unwrap_or(BindingSchema::Any);
+                BindingSchema::List(Box::new(item))
+            }
+            Value::Object(map) => BindingSchema::Object(
+                map.iter()
+                    .map(|(k, v)| (k.clone(), BindingSchema::from_value(v)))
+                    .collect(),
+            ),
+        }
+    }
+
+    /// A payload with this shape; [`from_value`](Self::from_value) round-trips
+    /// it, so it can be written to disk for `sc check-bindings`.
+    pub fn sample(&self) -> Value {
+        match self {
+            BindingSchema::Scalar => Value::String(String::new()),
+            BindingSchema::Any => Value::Null,
+            BindingSchema::List(item) => Value::Array(vec![item.sample()]),
+            BindingSchema::Object(fields) => Value::Object(
+                fields.iter().map(|(k, v)| (k.clone(), v.sample())).collect(),
+            ),
+        }
+    }
+
+    /// Resolves a dotted data path the way the runtime's `resolvePath` would.
+    pub fn lookup(&self, path: &str) -> Option<&BindingSchema> {
+        let mut current = self;
+        for segment in path.split('.') {
+            current = match current {
+                BindingSchema::Any => return Some(current),
+                BindingSchema::Object(fields) => fields.get(segment)?,
+                BindingSchema::List(item) if segment.parse::<usize>().is_ok() => item,
+                _ => return None,
+            };
+        }
+        Some(current)
+    }
+
+    fn has_item_field(&self, field: &str) -> bool {
+        match self {
+            BindingSchema::Any => true,
+            BindingSchema::Object(fields) => fields.contains_key(field),
+            _ => false,
+        }
+    }
+
+    fn any_item_has_field(&self, field: &str) -> bool {
+        match self {
+            BindingSchema::Any => true,
+            BindingSchema::List(item) => item.has_item_field(field) || item.any_item_has_field(field),
+            BindingSchema::Object(fields) => fields.values().any(|f| f.any_item_has_field(field)),
+            BindingSchema::Scalar => false,
+        }
+    }
+}
+
+// ════════════════════════════════════════════════════════════
+// 2. The Bindable Trait
+// ════════════════════════════════════════════════════════════
+
+/// Types that can describe the JSON shape they serialize to.
+///
+/// Derive it with `#[derive(Bindable)]` next to `Serialize`; the derive
+/// follows serde's renaming so the schema matches the wire format.
+pub trait Bindable {
+    fn binding_schema() -> BindingSchema;
+}
+
+macro_rules! scalar_bindable {
+    ($($ty:ty),* $(,)?) => {
+        $(impl Bindable for $ty {
+            fn binding_schema() -> BindingSchema {
+                BindingSchema::Scalar
+            }
+        })*
+    };
+}
+
+scalar_bindable!(
+    bool, char, String, str, (), i8, i16, i32, i64, i128, isize, u8, u16, u32, u64, u128, usize,
+    f32, f64,
+);
+
+impl<T: Bindable> Bindable for Option<T> {
+    fn binding_schema() -> BindingSchema {
+        T::binding_schema()
+    }
+}
+
+macro_rules! transparent_bindable {
+    ($($ty:ident),*) => {
+        $(impl<T: Bindable + ?Sized> Bindable for $ty<T> {
+            fn binding_schema() -> BindingSchema {
+                T::binding_schema()
+            }
+        })*
+    };
+}
+
+transparent_bindable!(Box, Rc, Arc);
+
+impl<T: Bindable + ?Sized> Bindable for &T {
+    fn binding_schema() -> BindingSchema {
+        T::binding_schema()
+    }
+}
+
+macro_rules! list_bindable {
+    ($($ty:ident),*) => {
+        $(impl<T: Bindable> Bindable for $ty<T> {
+            fn binding_schema() -> BindingSchema {
+                BindingSchema::List(Box::new(T::binding_schema()))
+            }
+        })*
+    };
+}
+
+list_bindable!(Vec, VecDeque, HashSet, BTreeSet);
+
+impl<T: Bindable> Bindable for [T] {
+    fn binding_schema() -> BindingSchema {
+        BindingSchema::List(Box::new(T::binding_schema()))
+    }
+}
+
+impl<T: Bindable, const N: usize> Bindable for [T; N] {
+    fn binding_schema() -> BindingSchema {
+        BindingSchema::List(Box::new(T::binding_schema()))
+    }
+}
+
+// Maps have data-dependent keys, so nothing below them can be checked
+impl<K, V> Bindable for HashMap<K, V> {
+    fn binding_schema() -> BindingSchema {
+        BindingSchema::Any
+    }
+}
+
+impl<K, V> Bindable for BTreeMap<K, V> {
+    fn binding_schema() -> BindingSchema {
+        BindingSchema::Any
+    }
+}
+
+impl Bindable for Value {
+    fn binding_schema() -> BindingSchema {
+        BindingSchema::Any
+    }
+}
+
+// ════════════════════════════════════════════════════════════
+// 3. Checking Markup Against a Schema
+// ════════════════════════════════════════════════════════════
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum IssueKind {
+    /// The path does not match the runtime's `PATH_RE`.
+    InvalidPath,
+    /// The path does not exist in the schema.
+    Missing,
+    /// An `s-list` points at something that is not an array.
+    NotAList,
+    /// A `.field` binding the enclosing list's items do not have.
+    NotAnItemField,
+    /// A `.field` binding outside any list template, which the runtime ignores.
+    OutsideList,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BindingIssue {
+    pub attribute: &'static str,
+    pub path: String,
+    pub kind: IssueKind,
+}
+
+impl fmt::Display for BindingIssue {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let reason = match self.kind {
+            IssueKind::InvalidPath => "invalid binding path",
+            IssueKind::Missing => "path not found in schema",
+            IssueKind::NotAList => "path is not a list",
+            IssueKind::NotAnItemField => "list items have no such field",
+            IssueKind::OutsideList => "item-local path used outside a list template",
+        };
+        write!(f, "{}=\"{}\": {}", self.attribute, self.path, reason)
+    }
+}
+
+impl BindingSchema {
+    /// Scans rendered HTML (e.g. Maud output) for `s-bind` and `s-list`
+    /// paths the schema cannot satisfy. `.field` bindings are checked
+    /// against the item shape of the list whose template contains them, or
+    /// whose pre-rendered items (the container's children) they sit in.
+    pub fn check(&self, html: &str) -> Vec<BindingIssue> {
+        let nodes = dom::parse(html);
+        let mut templates = HashMap::new();
+        dom::walk(&nodes, &mut |el| {
+            if el.name == "template" {
+                if let Some(id) = el.attr("id") {
+                    templates.entry(id.to_string()).or_insert(el);
+                }
+            }
+        });
+
+        let mut issues = Vec::new();
+        self.check_nodes(&nodes, None, &templates, &mut issues);
+        issues
+    }
+
+    /// Walks rendered markup, scoping `.field` bindings to `item`, the item
+    /// shape of the innermost enclosing list.
+    fn check_nodes(
+        &self,
+        nodes: &[Node],
+        item: Option<&BindingSchema>,
+        templates: &HashMap<String, &Element>,
+        issues: &mut Vec<BindingIssue>,
+    ) {
+        for el in nodes.iter().filter_map(Node::as_element) {
+            if let Some(raw) = el.attr("s-bind") {
+                issues.extend(self.check_bind(raw, item));
+            }
+            if el.name == "template" {
+                continue;
+            }
+            match el.attr("s-list") {
+                Some(list) => {
+                    // A bad list path is reported once; its items are left unchecked
+                    let list_item = self
+                        .check_list(el, list, templates, issues)
+                        .unwrap_or(&BindingSchema::Any);
+                    self.check_nodes(&el.children, Some(list_item), templates, issues);
+                }
+                None => self.check_nodes(&el.children, item, templates, issues),
+            }
+        }
+    }
+
+    /// Panics with every issue found by [`check`](Self::check); meant for
+    /// unit tests that render a template with sample data.
+    #[track_caller]
+    pub fn assert_html(&self, html: &str) {
+        let issues = self.check(html);
+        if !issues.is_empty() {
+            let lines: Vec<String> = issues.iter().map(|i| format!("  {i}")).collect();
+            panic!("binding contract violated:\n{}", lines.join("\n"));
+        }
+    }
+
+    /// Checks one attribute value without its surrounding markup, as needed
+    /// when scanning template sources. `.field` paths pass if any list in the
+    /// schema has items with that field.
+    pub fn check_attribute(&self, attribute: &str, value: &str) -> Option<BindingIssue> {
+        match attribute {
+            "s-bind" => {
+                let (path, _) = parse_bind(value);
+                if !is_valid_path(path) {
+                    return Some(issue("s-bind", path, IssueKind::InvalidPath));
+                }
+                match path.strip_prefix('.') {
+                    Some(field) => (!self.any_item_has_field(field))
+                        .then(|| issue("s-bind", path, IssueKind::NotAnItemField)),
+                    None => self.check_bind(value, None),
+                }
+            }
+            "s-list" => self.check_list_path(value).err(),
+            _ => None,
+        }
+    }
+
+    fn check_bind(&self, raw: &str, item: Option<&BindingSchema>) -> Option<BindingIssue> {
+        let (path, _) = parse_bind(raw);
+        if !is_valid_path(path) {
+            return Some(issue("s-bind", path, IssueKind::InvalidPath));
+        }
+        match (path.strip_prefix('.'), item) {
+            (Some(_), None) => Some(issue("s-bind", path, IssueKind::OutsideList)),
+            (Some(field), Some(item)) => (field.contains('.') || !item.has_item_field(field))
+                .then(|| issue("s-bind", path, IssueKind::NotAnItemField)),
+            (None, _) => self
+                .lookup(path)
+                .is_none()
+                .then(|| issue("s-bind", path, IssueKind::Missing)),
+        }
+    }
+
+    fn check_list_path(&self, path: &str) -> Result<&BindingSchema, BindingIssue> {
+        if !is_valid_path(path) || path.starts_with('.') {
+            return Err(issue("s-list", path, IssueKind::InvalidPath));
+        }
+        match self.lookup(path) {
+            None => Err(issue("s-list", path, IssueKind::Missing)),
+            Some(BindingSchema::List(item)) => Ok(item),
+            Some(any @ BindingSchema::Any) => Ok(any),
+            Some(_) => Err(issue("s-list", path, IssueKind::NotAList)),
+        }
+    }
+
+    fn check_list(
+        &self,
+        container: &Element,
+        path: &str,
+        templates: &HashMap<String, &Element>,
+        issues: &mut Vec<BindingIssue>,
+    ) -> Option<&BindingSchema> {
+        let item = match self.check_list_path(path) {
+            Ok(item) => item,
+            Err(err) => {
+                issues.push(err);
+                return None;
+            }
+        };
+
+        let named = container
+            .attr("s-template")
+            .and_then(|id| templates.get(id).copied());
+        let children = container.element_children().filter(|el| el.name == "template");
+
+        for tpl in named.into_iter().chain(children) {
+            let mut check = |el: &Element| {
+                if let Some(raw) = el.attr("s-bind") {
+                    issues.extend(self.check_bind(raw, Some(item)));
+                }
+            };
+            for node in &tpl.children {
+                if let Node::Element(el) = node {
+                    check(el);
+                    dom::walk(&el.children, &mut check);
+                }
+            }
+        }
+        Some(item)
+    }
+}
+
+fn issue(attribute: &'static str, path: &str, kind: IssueKind) -> BindingIssue {
+    BindingIssue {
+        attribute,
+        path: path.to_string(),
+        kind,
+    }
+}
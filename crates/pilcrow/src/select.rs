@@ -4,6 +4,8 @@
 };
 // use std::convert::Infallible;
 
+use crate::response::escape_attr;
+
 // ════════════════════════════════════════════════════════════
 // 1. The Unified Application Error
 // ════════════════════════════════════════════════════════════
@@ -14,6 +16,53 @@ pub enum AppError {
     Internal(anyhow::Error),
     /// A 404 Not Found (e.g., requested user doesn't exist)
     NotFound(String),
+    /// A client error rendered in the format the request negotiated
+    Rejected(Rejection),
+}
+
+/// A 4xx error that answers JSON requests with `{"error": ...}` and
+/// everyone else with a `<p class="silcrow-error">` HTML fragment.
+///
+/// silcrow.js only swaps 422s; any other status fails the navigation and
+/// reaches the page as a `silcrow:error` event, so the body is mainly for
+/// hard loads and API clients.
+#[derive(Debug, Clone)]
+pub struct Rejection {
+    pub status: StatusCode,
+    pub message: String,
+    pub mode: RequestMode,
+}
+
+impl Rejection {
+    pub fn new(status: StatusCode, message: impl Into<String>, req: &SilcrowRequest) -> Self {
+        Rejection {
+            status,
+            message: message.into(),
+            mode: req.preferred_mode(),
+        }
+    }
+}
+
+impl IntoResponse for Rejection {
+    fn into_response(self) -> Response {
+        match self.mode {
+            RequestMode::Json => (
+                self.status,
+                axum::Json(serde_json::json!({ "error": self.message })),
+            )
+                .into_response(),
+            // Clients of custom representations get the message as plain text
+            RequestMode::Other(_) => (self.status, self.message).into_response(),
+            RequestMode::Html | RequestMode::Navigate => (
+                self.status,
+                axum::response::Html(format!(
+                    "<p class=\"silcrow-error\">{}</p>",
+                    escape_attr(&self.message)
+                )),
+            )
+                .into_response(),
+        }
+    }
 }
 
 // Map your custom AppError to standard Axum responses
@@ -25,6 +74,17 @@ fn into_response(self) -> Response {
                 (StatusCode::INTERNAL_SERVER_ERROR, "Something went wrong").into_response()
             }
             AppError::NotFound(msg) => (StatusCode::NOT_FOUND, msg).into_response(),
+            AppError::Rejected(rejection) => rejection.into_response(),
+        }
+    }
+}
+
+impl std::fmt::Display for AppError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            AppError::Internal(err) => write!(f, "{err:#}"),
+            AppError::NotFound(msg) => write!(f, "not found: {msg}"),
+            AppError::Rejected(rejection) => write!(f, "{}: {}", rejection.status, rejection.message),
         }
     }
 }
@@ -43,48 +103,77 @@ fn from(err: E) -> Self {
 // 2. The Responses Container (with Type-Safe Builder)
 // ════════════════════════════════════════════════════════════
 
-/// Holds the closures for each potential response format.
-pub struct Responses<H, J, N> {
+type CustomResponse<'a> = Box<dyn FnOnce() -> Result<Response, AppError> + 'a>;
+
+/// Holds the closures for each potential response format. `'a` lets
+/// [`media`](Self::media) closures borrow the handler's locals, like the others.
+pub struct Responses<'a, H, J, N> {
     html: Option<H>,
     json: Option<J>,
     navigate: Option<N>,
+    custom: Vec<(&'static str, CustomResponse<'a>)>,
 }
 
-impl Responses<(), (), ()> {
+impl Responses<'_, (), (), ()> {
     /// Starts an empty set of responses
     pub fn new() -> Self {
         Self {
             html: None,
             json: None,
             navigate: None,
+            custom: Vec::new(),
         }
     }
 }
 
-impl<H, J, N> Responses<H, J, N> {
-    pub fn html<NewH>(self, f: NewH) -> Responses<NewH, J, N> {
+impl Default for Responses<'_, (), (), ()> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<'a, H, J, N> Responses<'a, H, J, N> {
+    pub fn html<NewH>(self, f: NewH) -> Responses<'a, NewH, J, N> {
         Responses {
             html: Some(f),
             json: self.json,
             navigate: self.navigate,
+            custom: self.custom,
         }
     }
 
-    pub fn json<NewJ>(self, f: NewJ) -> Responses<H, NewJ, N> {
+    pub fn json<NewJ>(self, f: NewJ) -> Responses<'a, H, NewJ, N> {
         Responses {
             html: self.html,
             json: Some(f),
             navigate: self.navigate,
+            custom: self.custom,
         }
     }
 
-    pub fn navigate<NewN>(self, f: NewN) -> Responses<H, J, NewN> {
+    pub fn navigate<NewN>(self, f: NewN) -> Responses<'a, H, J, NewN> {
         Responses {
             html: self.html,
             json: self.json,
             navigate: Some(f),
+            custom: self.custom,
         }
     }
+
+    /// Registers another representation by media type (`"text/csv"`,
+    /// `"text/plain"`, ...). It's chosen when `Accept` ranks it above HTML
+    /// and JSON; see [`SilcrowRequest::mode_among`].
+    pub fn media<F, T>(mut self, media_type: &'static str, f: F) -> Self
+    where
+        F: FnOnce() -> Result<T, AppError> + 'a,
+        T: IntoResponse,
+    {
+        self.custom.push((
+            media_type,
+            Box::new(move || f().map(IntoResponse::into_response)),
+        ));
+        self
+    }
 }
 
 // ════════════════════════════════════════════════════════════
@@ -93,12 +182,16 @@ pub fn navigate<NewN>(self, f: NewN) -> Responses<H, J, NewN> {
 
 // Assuming RequestMode is imported from Phase 1
 use crate::extract::{RequestMode, SilcrowRequest};
+use crate::router::add_vary;
 
 impl SilcrowRequest {
     /// Evaluates the preferred mode and executes *only* the matching closure.
+    ///
+    /// A missing representation is answered with a negotiated `406`; every
+    /// answer carries `Vary: Accept`.
     pub fn select<H, J, N, THtml, TJson, TNav>(
         &self,
-        responses: Responses<H, J, N>,
+        responses: Responses<'_, H, J, N>,
     ) -> Result<Response, AppError>
     where
         H: FnOnce() -> Result<THtml, AppError>,
@@ -108,39 +201,33 @@ pub fn select<H, J, N, THtml, TJson, TNav>(
         TJson: IntoResponse,
         TNav: IntoResponse,
     {
-        match self.preferred_mode() {
-            RequestMode::Html => {
-                if let Some(f) = responses.html {
-                    Ok(f()?.into_response())
-                } else {
-                    Ok((
-                        StatusCode::NOT_ACCEPTABLE,
-                        "HTML representation not provided",
-                    )
-                        .into_response())
-                }
-            }
-            RequestMode::Json => {
-                if let Some(f) = responses.json {
-                    Ok(f()?.into_response())
-                } else {
-                    Ok((
-                        StatusCode::NOT_ACCEPTABLE,
-                        "JSON representation not provided",
-                    )
-                        .into_response())
-                }
-            }
-            RequestMode::Navigate => {
-                if let Some(f) = responses.navigate {
-                    Ok(f()?.into_response())
-                } else {
-                    Ok(
-                        (StatusCode::NOT_ACCEPTABLE, "Navigation rule not provided")
-                            .into_response(),
-                    )
-                }
-            }
-        }
+        let custom: Vec<&'static str> = responses.custom.iter().map(|(media, _)| *media).collect();
+        let rendered = match self.mode_among(&custom) {
+            RequestMode::Other(media) => responses
+                .custom
+                .into_iter()
+                .find_map(|(m, f)| (m == media).then_some(f))
+                .map(|f| f())
+                .ok_or("Representation not provided"),
+            RequestMode::Html => responses
+                .html
+                .map(|f| f().map(IntoResponse::into_response))
+                .ok_or("HTML representation not provided"),
+            RequestMode::Json => responses
+                .json
+                .map(|f| f().map(IntoResponse::into_response))
+                .ok_or("JSON representation not provided"),
+            RequestMode::Navigate => responses
+                .navigate
+                .map(|f| f().map(IntoResponse::into_response))
+                .ok_or("Navigation rule not provided"),
+        };
+
+        let mut response = match rendered {
+            Ok(response) => response?,
+            Err(missing) => Rejection::new(StatusCode::NOT_ACCEPTABLE, missing, self).into_response(),
+        };
+        add_vary(response.headers_mut(), &["Accept"]);
+        Ok(response)
     }
 }
@@ -0,0 +1,504 @@
+// ./crates/pilcrow/src/stream.rs
+
+use std::io;
+
+use axum::body::{Body, Bytes};
+use axum::http::{header, HeaderValue, StatusCode};
+use axum::response::{IntoResponse, Response};
+use futures_util::stream::{self, Iter, Stream, StreamExt};
+use serde::ser::{self, Serialize};
+
+use crate::response::{BaseResponse, ResponseExt};
+
+// ════════════════════════════════════════════════════════════
+// 1. CSV
+// ════════════════════════════════════════════════════════════
+
+/// Streams rows as `text/csv`, one record per item.
+///
+/// Structs and maps write a header row from the first record's field names;
+/// later records are written in that column order, with fields they skip
+/// left empty. Sequences and tuples write values only. Nested values end up
+/// as JSON text in their cell. Cells a spreadsheet would read as a formula
+/// get a leading `'` unless [`escape_formulas`](Self::escape_formulas) is off.
+///
+/// The body is written as rows arrive, so a row that fails to serialize, or
+/// has a field the header row lacks, cuts the response short rather than
+/// turning it into a 500.
+pub struct CsvResponse<S> {
+    pub rows: S,
+    pub base: BaseResponse,
+    pub filename: Option<String>,
+    pub escape_formulas: bool,
+}
+
+/// A CSV response over an iterator of rows.
+pub fn csv<I>(rows: I) -> CsvResponse<Iter<I::IntoIter>>
+where
+    I: IntoIterator,
+{
+    csv_stream(stream::iter(rows))
+}
+
+/// A CSV response over a [`Stream`] of rows, e.g. a database cursor.
+pub fn csv_stream<S: Stream>(rows: S) -> CsvResponse<S> {
+    CsvResponse {
+        rows,
+        base: BaseResponse::default(),
+        filename: None,
+        escape_formulas: true,
+    }
+}
+
+impl<S> CsvResponse<S> {
+    /// Sends the CSV as a download named `filename`.
+    pub fn filename(mut self, filename: impl Into<String>) -> Self {
+        self.filename = Some(filename.into());
+        self
+    }
+
+    /// With `false`, cells starting with `=`, `+`, `-`, `@`, tab or CR are
+    /// written verbatim; only for files never opened in a spreadsheet.
+    pub fn escape_formulas(mut self, enabled: bool) -> Self {
+        self.escape_formulas = enabled;
+        self
+    }
+}
+
+impl<S> IntoResponse for CsvResponse<S>
+where
+    S: Stream + Send + 'static,
+    S::Item: Serialize,
+{
+    fn into_response(self) -> Response {
+        let escape = self.escape_formulas;
+        // Pinned by the first record; `None` until then
+        let mut columns: Option<Vec<String>> = None;
+        let body = self.rows.map(move |row| {
+            let record = row
+                .serialize(RecordSerializer)
+                .map_err(|err| io::Error::new(io::ErrorKind::InvalidData, err))?;
+
+            let mut out = String::new();
+            let cells = match &columns {
+                None => {
+                    if !record.headers.is_empty() {
+                        write_csv_line(&mut out, &record.headers, escape);
+                    }
+                    columns = Some(record.headers);
+                    record.cells
+                }
+                Some(columns) => align(columns, record)?,
+            };
+            write_csv_line(&mut out, &cells, escape);
+            Ok::<_, io::Error>(Bytes::from(out))
+        });
+
+        let mut response = streaming(body, "text/csv; charset=utf-8");
+        if let Some(filename) = &self.filename {
+            let disposition = format!("attachment; filename=\"{}\"", filename.replace(['"', '\\'], "_"));
+            if let Ok(value) = HeaderValue::from_str(&disposition) {
+                response.headers_mut().insert(header::CONTENT_DISPOSITION, value);
+            }
+        }
+        self.base.apply_to_response(&mut response);
+        response
+    }
+}
+
+/// Orders a record's cells by the pinned header row, leaving skipped fields empty.
+fn align(columns: &[String], record: Record) -> io::Result<Vec<String>> {
+    if columns.is_empty() || record.headers == columns {
+        return Ok(record.cells);
+    }
+    let mut cells = vec![String::new(); columns.len()];
+    for (name, cell) in record.headers.into_iter().zip(record.cells) {
+        let Some(index) = columns.iter().position(|c| *c == name) else {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidData,
+                format!("CSV row has field `{name}`, which is not in the header row"),
+            ));
+        };
+        cells[index] = cell;
+    }
+    Ok(cells)
+}
+
+/// RFC 4180: quote cells holding separators, quotes or line breaks. With
+/// `escape`, cells that would start a spreadsheet formula are prefixed with `'`.
+fn write_csv_line(out: &mut String, cells: &[String], escape: bool) {
+    for (index, cell) in cells.iter().enumerate() {
+        if index > 0 {
+            out.push(',');
+        }
+        let escaped;
+        let cell = if escape && is_formula(cell) {
+            escaped = format!("'{cell}");
+            &escaped
+        } else {
+            cell
+        };
+        if cell.contains([',', '"', '\n', '\r']) {
+            out.push('"');
+            out.push_str(&cell.replace('"', "\"\""));
+            out.push('"');
+        } else {
+            out.push_str(cell);
+        }
+    }
+    out.push_str("\r\n");
+}
+
+/// Cells starting with a formula trigger, other than plain negative numbers.
+fn is_formula(cell: &str) -> bool {
+    cell.starts_with(['=', '+', '-', '@', '\t', '\r']) && cell.parse::<f64>().is_err()
+}
+
+// ════════════════════════════════════════════════════════════
+// 2. NDJSON
+// ════════════════════════════════════════════════════════════
+
+/// Streams items as `application/x-ndjson`, one JSON document per line.
+pub struct NdjsonResponse<S> {
+    pub items: S,
+    pub base: BaseResponse,
+}
+
+/// An NDJSON response over an iterator of items.
+pub fn ndjson<I>(items: I) -> NdjsonResponse<Iter<I::IntoIter>>
+where
+    I: IntoIterator,
+{
+    ndjson_stream(stream::iter(items))
+}
+
+/// An NDJSON response over a [`Stream`] of items.
+pub fn ndjson_stream<S: Stream>(items: S) -> NdjsonResponse<S> {
+    NdjsonResponse {
+        items,
+        base: BaseResponse::default(),
+    }
+}
+
+impl<S> IntoResponse for NdjsonResponse<S>
+where
+    S: Stream + Send + 'static,
+    S::Item: Serialize,
+{
+    fn into_response(self) -> Response {
+        let body = self.items.map(|item| {
+            let mut line = serde_json::to_vec(&item)?;
+            line.push(b'\n');
+            Ok::<_, io::Error>(Bytes::from(line))
+        });
+
+        let mut response = streaming(body, "application/x-ndjson");
+        self.base.apply_to_response(&mut response);
+        response
+    }
+}
+
+fn streaming<S>(body: S, content_type: &'static str) -> Response
+where
+    S: Stream<Item = io::Result<Bytes>> + Send + 'static,
+{
+    (
+        StatusCode::OK,
+        [(header::CONTENT_TYPE, HeaderValue::from_static(content_type))],
+        Body::from_stream(body),
+    )
+        .into_response()
+}
+
+impl<S> ResponseExt for CsvResponse<S> {
+    fn base_mut(&mut self) -> &mut BaseResponse {
+        &mut self.base
+    }
+}
+impl<S> ResponseExt for NdjsonResponse<S> {
+    fn base_mut(&mut self) -> &mut BaseResponse {
+        &mut self.base
+    }
+}
+
+// ════════════════════════════════════════════════════════════
+// 3. Flattening a Row into Cells
+// ════════════════════════════════════════════════════════════
+
+#[derive(Default)]
+struct Record {
+    headers: Vec<String>,
+    cells: Vec<String>,
+}
+
+/// A single cell: strings as-is, `null` empty, anything nested as JSON.
+fn cell<T: Serialize + ?Sized>(value: &T) -> Result<String, serde_json::Error> {
+    Ok(match serde_json::to_value(value)? {
+        serde_json::Value::Null => String::new(),
+        serde_json::Value::String(s) => s,
+        other => other.to_string(),
+    })
+}
+
+/// Serializes one row into a [`Record`]; scalars become a one-cell row.
+struct RecordSerializer;
+
+impl RecordSerializer {
+    fn scalar<T: Serialize + ?Sized>(value: &T) -> Result<Record, serde_json::Error> {
+        Ok(Record {
+            headers: Vec::new(),
+            cells: vec![cell(value)?],
+        })
+    }
+}
+
+macro_rules! serialize_scalar {
+    ($($method:ident: $ty:ty),* $(,)?) => {
+        $(fn $method(self, v: $ty) -> Result<Record, serde_json::Error> {
+            Self::scalar(&v)
+        })*
+    };
+}
+
+impl ser::Serializer for RecordSerializer {
+    type Ok = Record;
+    type Error = serde_json::Error;
+    type SerializeSeq = RecordBuilder;
+    type SerializeTuple = RecordBuilder;
+    type SerializeTupleStruct = RecordBuilder;
+    type SerializeTupleVariant = RecordBuilder;
+    type SerializeMap = RecordBuilder;
+    type SerializeStruct = RecordBuilder;
+    type SerializeStructVariant = RecordBuilder;
+
+    serialize_scalar! {
+        serialize_bool: bool,
+        serialize_i8: i8,
+        serialize_i16: i16,
+        serialize_i32: i32,
+        serialize_i64: i64,
+        serialize_i128: i128,
+        serialize_u8: u8,
+        serialize_u16: u16,
+        serialize_u32: u32,
+        serialize_u64: u64,
+        serialize_u128: u128,
+        serialize_f32: f32,
+        serialize_f64: f64,
+        serialize_char: char,
+        serialize_str: &str,
+        serialize_bytes: &[u8],
+    }
+
+    fn serialize_none(self) -> Result<Record, serde_json::Error> {
+        Self::scalar(&())
+    }
+
+    fn serialize_some<T: Serialize + ?Sized>(self, value: &T) -> Result<Record, serde_json::Error> {
+        value.serialize(self)
+    }
+
+    fn serialize_unit(self) -> Result<Record, serde_json::Error> {
+        Self::scalar(&())
+    }
+
+    fn serialize_unit_struct(self, _name: &'static str) -> Result<Record, serde_json::Error> {
+        Self::scalar(&())
+    }
+
+    fn serialize_unit_variant(
+        self,
+        _name: &'static str,
+        _index: u32,
+        variant: &'static str,
+    ) -> Result<Record, serde_json::Error> {
+        Self::scalar(variant)
+    }
+
+    fn serialize_newtype_struct<T: Serialize + ?Sized>(
+        self,
+        _name: &'static str,
+        value: &T,
+    ) -> Result<Record, serde_json::Error> {
+        value.serialize(self)
+    }
+
+    fn serialize_newtype_variant<T: Serialize + ?Sized>(
+        self,
+        _name: &'static str,
+        _index: u32,
+        _variant: &'static str,
+        value: &T,
+    ) -> Result<Record, serde_json::Error> {
+        value.serialize(self)
+    }
+
+    fn serialize_seq(self, _len: Option<usize>) -> Result<RecordBuilder, serde_json::Error> {
+        Ok(RecordBuilder::default())
+    }
+
+    fn serialize_tuple(self, _len: usize) -> Result<RecordBuilder, serde_json::Error> {
+        Ok(RecordBuilder::default())
+    }
+
+    fn serialize_tuple_struct(
+        self,
+        _name: &'static str,
+        _len: usize,
+    ) -> Result<RecordBuilder, serde_json::Error> {
+        Ok(RecordBuilder::default())
+    }
+
+    fn serialize_tuple_variant(
+        self,
+        _name: &'static str,
+        _index: u32,
+        _variant: &'static str,
+        _len: usize,
+    ) -> Result<RecordBuilder, serde_json::Error> {
+        Ok(RecordBuilder::default())
+    }
+
+    fn serialize_map(self, _len: Option<usize>) -> Result<RecordBuilder, serde_json::Error> {
+        Ok(RecordBuilder::default())
+    }
+
+    fn serialize_struct(
+        self,
+        _name: &'static str,
+        _len: usize,
+    ) -> Result<RecordBuilder, serde_json::Error> {
+        Ok(RecordBuilder::default())
+    }
+
+    fn serialize_struct_variant(
+        self,
+        _name: &'static str,
+        _index: u32,
+        _variant: &'static str,
+        _len: usize,
+    ) -> Result<RecordBuilder, serde_json::Error> {
+        Ok(RecordBuilder::default())
+    }
+}
+
+#[derive(Default)]
+struct RecordBuilder {
+    record: Record,
+}
+
+impl RecordBuilder {
+    fn value<T: Serialize + ?Sized>(&mut self, value: &T) -> Result<(), serde_json::Error> {
+        self.record.cells.push(cell(value)?);
+        Ok(())
+    }
+
+    fn field<T: Serialize + ?Sized>(&mut self, key: &str, value: &T) -> Result<(), serde_json::Error> {
+        self.record.headers.push(key.to_string());
+        self.value(value)
+    }
+}
+
+impl ser::SerializeSeq for RecordBuilder {
+    type Ok = Record;
+    type Error = serde_json::Error;
+
+    fn serialize_element<T: Serialize + ?Sized>(&mut self, value: &T) -> Result<(), serde_json::Error> {
+        self.value(value)
+    }
+
+    fn end(self) -> Result<Record, serde_json::Error> {
+        Ok(self.record)
+    }
+}
+
+impl ser::SerializeTuple for RecordBuilder {
+    type Ok = Record;
+    type Error = serde_json::Error;
+
+    fn serialize_element<T: Serialize + ?Sized>(&mut self, value: &T) -> Result<(), serde_json::Error> {
+        self.value(value)
+    }
+
+    fn end(self) -> Result<Record, serde_json::Error> {
+        Ok(self.record)
+    }
+}
+
+impl ser::SerializeTupleStruct for RecordBuilder {
+    type Ok = Record;
+    type Error = serde_json::Error;
+
+    fn serialize_field<T: Serialize + ?Sized>(&mut self, value: &T) -> Result<(), serde_json::Error> {
+        self.value(value)
+    }
+
+    fn end(self) -> Result<Record, serde_json::Error> {
+        Ok(self.record)
+    }
+}
+
+impl ser::SerializeTupleVariant for RecordBuilder {
+    type Ok = Record;
+    type Error = serde_json::Error;
+
+    fn serialize_field<T: Serialize + ?Sized>(&mut self, value: &T) -> Result<(), serde_json::Error> {
+        self.value(value)
+    }
+
+    fn end(self) -> Result<Record, serde_json::Error> {
+        Ok(self.record)
+    }
+}
+
+impl ser::SerializeMap for RecordBuilder {
+    type Ok = Record;
+    type Error = serde_json::Error;
+
+    fn serialize_key<T: Serialize + ?Sized>(&mut self, key: &T) -> Result<(), serde_json::Error> {
+        self.record.headers.push(cell(key)?);
+        Ok(())
+    }
+
+    fn serialize_value<T: Serialize + ?Sized>(&mut self, value: &T) -> Result<(), serde_json::Error> {
+        self.value(value)
+    }
+
+    fn end(self) -> Result<Record, serde_json::Error> {
+        Ok(self.record)
+    }
+}
+
+impl ser::SerializeStruct for RecordBuilder {
+    type Ok = Record;
+    type Error = serde_json::Error;
+
+    fn serialize_field<T: Serialize + ?Sized>(
+        &mut self,
+        key: &'static str,
+        value: &T,
+    ) -> Result<(), serde_json::Error> {
+        self.field(key, value)
+    }
+
+    fn end(self) -> Result<Record, serde_json::Error> {
+        Ok(self.record)
+    }
+}
+
+impl ser::SerializeStructVariant for RecordBuilder {
+    type Ok = Record;
+    type Error = serde_json::Error;
+
+    fn serialize_field<T: Serialize + ?Sized>(
+        &mut self,
+        key: &'static str,
+        value: &T,
+    ) -> Result<(), serde_json::Error> {
+        self.field(key, value)
+    }
+
+    fn end(self) -> Result<Record, serde_json::Error> {
+        Ok(self.record)
+    }
+}
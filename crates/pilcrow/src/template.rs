@@ -0,0 +1,143 @@
+// ./crates/pilcrow/src/template.rs
+
+use crate::extract::SilcrowRequest;
+use crate::response::{html, HtmlResponse};
+use crate::select::AppError;
+
+// ════════════════════════════════════════════════════════════
+// 1. Template Trait
+// ════════════════════════════════════════════════════════════
+
+/// A page template pilcrow can render into an [`HtmlResponse`].
+///
+/// Implemented for every `askama::Template` (feature `askama`) and for
+/// [`MiniJinja`] templates (feature `minijinja`).
+pub trait Template {
+    /// Renders the whole page.
+    fn render_page(&self) -> Result<String, AppError>;
+
+    /// Renders only the named block, or `None` if the template has no such
+    /// block (or can't render blocks by name), in which case callers fall
+    /// back to the whole page.
+    fn render_block(&self, block: &str) -> Result<Option<String>, AppError> {
+        let _ = block;
+        Ok(None)
+    }
+}
+
+/// Pairs a page with a separately compiled fragment for one of its blocks.
+///
+/// Askama resolves blocks at compile time, so a fragment is its own template
+/// type extending the same layout; see [`with_block`].
+pub struct WithBlock<P, F> {
+    pub page: P,
+    pub block: &'static str,
+    pub fragment: F,
+}
+
+/// Serves `fragment` when `block` is requested and `page` otherwise.
+pub fn with_block<P: Template, F: Template>(
+    page: P,
+    block: &'static str,
+    fragment: F,
+) -> WithBlock<P, F> {
+    WithBlock {
+        page,
+        block,
+        fragment,
+    }
+}
+
+impl<P: Template, F: Template> Template for WithBlock<P, F> {
+    fn render_page(&self) -> Result<String, AppError> {
+        self.page.render_page()
+    }
+
+    fn render_block(&self, block: &str) -> Result<Option<String>, AppError> {
+        if block == self.block {
+            self.fragment.render_page().map(Some)
+        } else {
+            self.page.render_block(block)
+        }
+    }
+}
+
+// ════════════════════════════════════════════════════════════
+// 2. Request Integration
+// ════════════════════════════════════════════════════════════
+
+impl SilcrowRequest {
+    /// Renders `template` for this request: just `block` for Silcrow
+    /// navigations, the full page for hard loads.
+    pub fn template<T: Template>(
+        &self,
+        template: &T,
+        block: &str,
+    ) -> Result<HtmlResponse, AppError> {
+        if self.is_silcrow {
+            if let Some(fragment) = template.render_block(block)? {
+                return Ok(html(fragment));
+            }
+        }
+        Ok(html(template.render_page()?))
+    }
+}
+
+// ════════════════════════════════════════════════════════════
+// 3. Askama
+// ════════════════════════════════════════════════════════════
+
+#[cfg(feature = "askama")]
+impl<T: askama::Template> Template for T {
+    fn render_page(&self) -> Result<String, AppError> {
+        Ok(askama::Template::render(self)?)
+    }
+}
+
+// ════════════════════════════════════════════════════════════
+// 4. MiniJinja
+// ════════════════════════════════════════════════════════════
+
+/// A named template in a MiniJinja environment together with its context.
+#[cfg(feature = "minijinja")]
+pub struct MiniJinja<'env, S> {
+    env: &'env minijinja::Environment<'env>,
+    name: String,
+    context: S,
+}
+
+/// Looks up `name` in `env` lazily, at render time.
+#[cfg(feature = "minijinja")]
+pub fn minijinja<'env, S: serde::Serialize>(
+    env: &'env minijinja::Environment<'env>,
+    name: impl Into<String>,
+    context: S,
+) -> MiniJinja<'env, S> {
+    MiniJinja {
+        env,
+        name: name.into(),
+        context,
+    }
+}
+
+#[cfg(feature = "minijinja")]
+impl<S: serde::Serialize> Template for MiniJinja<'_, S> {
+    fn render_page(&self) -> Result<String, AppError> {
+        let template = self.env.get_template(&self.name)?;
+        Ok(template.render(&self.context)?)
+    }
+
+    fn render_block(&self, block: &str) -> Result<Option<String>, AppError> {
+        let template = self.env.get_template(&self.name)?;
+        // Runs only the top level (`extends`, `set`, imports) into a discarding
+        // output, which skips every block; then renders just the one asked for.
+        // `render_captured` would render the whole page to get the same state.
+        #[allow(deprecated)]
+        let mut state = template.eval_to_state(&self.context)?;
+        match state.render_block(block) {
+            Ok(fragment) => Ok(Some(fragment)),
+            Err(err) if err.kind() == minijinja::ErrorKind::UnknownBlock => Ok(None),
+            Err(err) => Err(err.into()),
+        }
+    }
+}
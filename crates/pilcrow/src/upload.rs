@@ -0,0 +1,439 @@
+// ./crates/pilcrow/src/upload.rs
+
+use std::path::PathBuf;
+
+use axum::async_trait;
+use axum::body::Bytes;
+use axum::extract::{FromRequest, Request};
+use axum::http::{header, StatusCode};
+use serde::de::DeserializeOwned;
+use tokio::io::AsyncWriteExt;
+
+use crate::extract::SilcrowRequest;
+use crate::select::{AppError, Rejection};
+
+// ════════════════════════════════════════════════════════════
+// 1. Sinks
+// ════════════════════════════════════════════════════════════
+
+/// Metadata of a file part, known before its content arrives.
+#[derive(Debug, Clone)]
+pub struct FileMeta {
+    pub field: String,
+    pub file_name: Option<String>,
+    pub content_type: Option<String>,
+}
+
+/// Where uploaded files are streamed to.
+///
+/// The extractor takes the sink from the request extensions
+/// (`.layer(Extension(TempDirSink::new(dir)))`) or falls back to `Default`.
+#[async_trait]
+pub trait UploadSink: Send + Sync {
+    type Writer: UploadWriter<Output = Self::Output>;
+    type Output: Send;
+
+    async fn open(&self, meta: &FileMeta) -> std::io::Result<Self::Writer>;
+
+    /// Releases a finished file whose request was rejected by a later part.
+    async fn discard(&self, _output: Self::Output) {}
+}
+
+#[async_trait]
+pub trait UploadWriter: Send {
+    type Output: Send;
+
+    async fn write(&mut self, chunk: &[u8]) -> std::io::Result<()>;
+    async fn finish(self) -> std::io::Result<Self::Output>;
+    /// Discards a partially written file after an error or limit breach.
+    async fn abort(self);
+}
+
+/// Buffers files in memory; only sensible with tight limits.
+#[derive(Debug, Clone, Default)]
+pub struct MemorySink;
+
+#[async_trait]
+impl UploadSink for MemorySink {
+    type Writer = Vec<u8>;
+    type Output = Bytes;
+
+    async fn open(&self, _meta: &FileMeta) -> std::io::Result<Vec<u8>> {
+        Ok(Vec::new())
+    }
+}
+
+#[async_trait]
+impl UploadWriter for Vec<u8> {
+    type Output = Bytes;
+
+    async fn write(&mut self, chunk: &[u8]) -> std::io::Result<()> {
+        self.extend_from_slice(chunk);
+        Ok(())
+    }
+
+    async fn finish(self) -> std::io::Result<Bytes> {
+        Ok(Bytes::from(self))
+    }
+
+    async fn abort(self) {}
+}
+
+/// Streams files into a directory under random names; the handler owns
+/// the returned paths and should move or delete them.
+#[derive(Debug, Clone)]
+pub struct TempDirSink {
+    dir: PathBuf,
+}
+
+impl TempDirSink {
+    pub fn new(dir: impl Into<PathBuf>) -> Self {
+        TempDirSink { dir: dir.into() }
+    }
+}
+
+impl Default for TempDirSink {
+    fn default() -> Self {
+        TempDirSink::new(std::env::temp_dir())
+    }
+}
+
+pub struct TempFileWriter {
+    // Declared first so the file is closed before the guard removes it
+    file: tokio::fs::File,
+    path: TempPath,
+}
+
+/// Deletes the file unless [`keep`](TempPath::keep) hands it on, so a failed
+/// `finish` or a request dropped mid-stream leaves nothing behind.
+struct TempPath(Option<PathBuf>);
+
+impl TempPath {
+    fn keep(mut self) -> PathBuf {
+        self.0.take().expect("kept once")
+    }
+}
+
+impl Drop for TempPath {
+    fn drop(&mut self) {
+        if let Some(path) = self.0.take() {
+            let _ = std::fs::remove_file(path);
+        }
+    }
+}
+
+#[async_trait]
+impl UploadSink for TempDirSink {
+    type Writer = TempFileWriter;
+    type Output = PathBuf;
+
+    async fn open(&self, _meta: &FileMeta) -> std::io::Result<TempFileWriter> {
+        let mut bytes = [0u8; 16];
+        getrandom::getrandom(&mut bytes).map_err(|e| std::io::Error::other(e.to_string()))?;
+        let name: String = bytes.iter().map(|b| format!("{b:02x}")).collect();
+
+        let path = self.dir.join(format!("silcrow-{name}.upload"));
+        let file = tokio::fs::File::create(&path).await?;
+        Ok(TempFileWriter {
+            file,
+            path: TempPath(Some(path)),
+        })
+    }
+
+    async fn discard(&self, path: PathBuf) {
+        let _ = tokio::fs::remove_file(&path).await;
+    }
+}
+
+#[async_trait]
+impl UploadWriter for TempFileWriter {
+    type Output = PathBuf;
+
+    async fn write(&mut self, chunk: &[u8]) -> std::io::Result<()> {
+        self.file.write_all(chunk).await
+    }
+
+    async fn finish(mut self) -> std::io::Result<PathBuf> {
+        self.file.flush().await?;
+        Ok(self.path.keep())
+    }
+
+    async fn abort(self) {
+        // The guard deletes the file
+        drop(self);
+    }
+}
+
+// ════════════════════════════════════════════════════════════
+// 2. Limits
+// ════════════════════════════════════════════════════════════
+
+/// Upload constraints, read from the request extensions
+/// (`.layer(Extension(UploadLimits { .. }))`) or defaulted.
+///
+/// These replace axum's `DefaultBodyLimit` for [`Uploads`].
+#[derive(Debug, Clone)]
+pub struct UploadLimits {
+    pub max_file_size: u64,
+    /// Cap on a single text field.
+    pub max_field_size: u64,
+    pub max_total_size: u64,
+    pub max_files: usize,
+    /// Accepted content types; `image/*` style wildcards allowed. Empty accepts all.
+    pub allowed_types: Vec<String>,
+}
+
+impl Default for UploadLimits {
+    fn default() -> Self {
+        UploadLimits {
+            max_file_size: 10 * 1024 * 1024,
+            max_field_size: 1024 * 1024,
+            max_total_size: 50 * 1024 * 1024,
+            max_files: 20,
+            allowed_types: Vec::new(),
+        }
+    }
+}
+
+impl UploadLimits {
+    fn allows(&self, content_type: Option<&str>) -> bool {
+        if self.allowed_types.is_empty() {
+            return true;
+        }
+        let Some(content_type) = content_type else {
+            return false;
+        };
+        let content_type = content_type.to_ascii_lowercase();
+        self.allowed_types.iter().any(|allowed| {
+            let allowed = allowed.to_ascii_lowercase();
+            match allowed.strip_suffix("/*") {
+                Some(prefix) => content_type.split('/').next() == Some(prefix),
+                None => content_type == allowed,
+            }
+        })
+    }
+}
+
+// ════════════════════════════════════════════════════════════
+// 3. The Extractor
+// ════════════════════════════════════════════════════════════
+
+#[derive(Debug, Clone)]
+pub struct UploadedFile<T> {
+    pub meta: FileMeta,
+    pub size: u64,
+    pub data: T,
+}
+
+/// A multipart submission with its files streamed into `S`.
+///
+/// Breaching a limit answers 413, a disallowed content type 415, both in
+/// the format the request negotiated. Files already stored for a rejected
+/// or abandoned request are discarded.
+pub struct Uploads<S: UploadSink = TempDirSink> {
+    pub files: Vec<UploadedFile<S::Output>>,
+    pub fields: Vec<(String, String)>,
+    request: SilcrowRequest,
+}
+
+impl<S: UploadSink> Uploads<S> {
+    /// Deserializes the text fields, rejecting bad input with a negotiated
+    /// 400 like [`SilcrowForm`](crate::SilcrowForm).
+    pub fn form<T: DeserializeOwned>(&self) -> Result<T, AppError> {
+        let encoded = serde_html_form::to_string(&self.fields)?;
+        serde_html_form::from_str(&encoded).map_err(|err| {
+            AppError::Rejected(Rejection::new(
+                StatusCode::BAD_REQUEST,
+                format!("Invalid form data: {err}"),
+                &self.request,
+            ))
+        })
+    }
+}
+
+#[async_trait]
+impl<S, St> FromRequest<St> for Uploads<S>
+where
+    S: UploadSink + Default + Clone + 'static,
+    S::Output: 'static,
+    St: Send + Sync,
+{
+    type Rejection = AppError;
+
+    async fn from_request(req: Request, _state: &St) -> Result<Self, Self::Rejection> {
+        let silcrow = SilcrowRequest::from_headers(req.headers());
+        let reject = |status: StatusCode, message: String| {
+            AppError::Rejected(Rejection::new(status, message, &silcrow))
+        };
+
+        let boundary = req
+            .headers()
+            .get(header::CONTENT_TYPE)
+            .and_then(|v| v.to_str().ok())
+            .and_then(|ct| multer::parse_boundary(ct).ok())
+            .ok_or_else(|| {
+                reject(StatusCode::UNSUPPORTED_MEDIA_TYPE, "Expected multipart/form-data".into())
+            })?;
+        let sink = req.extensions().get::<S>().cloned().unwrap_or_default();
+        let limits = req.extensions().get::<UploadLimits>().cloned().unwrap_or_default();
+
+        // Backstop for the raw stream, which also carries part headers and boundaries
+        let constraints = multer::Constraints::new().size_limit(
+            multer::SizeLimit::new()
+                .whole_stream(limits.max_total_size.saturating_add(MULTIPART_OVERHEAD)),
+        );
+        let mut multipart = multer::Multipart::with_constraints(
+            req.into_body().into_data_stream(),
+            boundary,
+            constraints,
+        );
+        let invalid = |e: multer::Error| match e {
+            multer::Error::StreamSizeExceeded { .. } => {
+                too_large(&reject, "Upload exceeds the total size limit")
+            }
+            e => reject(StatusCode::BAD_REQUEST, format!("Invalid upload: {e}")),
+        };
+        let mut uploads = Uploads {
+            files: Vec::new(),
+            fields: Vec::new(),
+            request: silcrow.clone(),
+        };
+        // Holds stored files until they reach the handler
+        let mut stored_files = DiscardGuard {
+            sink: Some(sink.clone()),
+            files: Vec::new(),
+        };
+        let mut total: u64 = 0;
+
+        let result = async {
+            while let Some(mut field) = multipart.next_field().await.map_err(invalid)? {
+                let name = field.name().unwrap_or_default().to_string();
+
+                let Some(file_name) = field.file_name().map(str::to_owned) else {
+                    // Text parts are checked as they arrive, never buffered past a limit
+                    let mut text = Vec::new();
+                    while let Some(chunk) = field.chunk().await.map_err(invalid)? {
+                        total += chunk.len() as u64;
+                        if text.len() + chunk.len() > limits.max_field_size as usize {
+                            return Err(too_large(&reject, "Field exceeds the size limit"));
+                        }
+                        if total > limits.max_total_size {
+                            return Err(too_large(&reject, "Upload exceeds the total size limit"));
+                        }
+                        text.extend_from_slice(&chunk);
+                    }
+                    let text = String::from_utf8(text).map_err(|_| {
+                        reject(StatusCode::BAD_REQUEST, format!("Field `{name}` is not valid UTF-8"))
+                    })?;
+                    uploads.fields.push((name, text));
+                    continue;
+                };
+
+                // Browsers send an empty, unnamed part for untouched file inputs
+                if file_name.is_empty() {
+                    continue;
+                }
+                if stored_files.files.len() >= limits.max_files {
+                    return Err(too_large(&reject, "Too many files"));
+                }
+
+                let meta = FileMeta {
+                    field: name,
+                    file_name: Some(file_name),
+                    content_type: field.content_type().map(|m| m.to_string()),
+                };
+                if !limits.allows(meta.content_type.as_deref()) {
+                    return Err(reject(
+                        StatusCode::UNSUPPORTED_MEDIA_TYPE,
+                        format!(
+                            "File type `{}` is not allowed",
+                            meta.content_type.as_deref().unwrap_or("unknown")
+                        ),
+                    ));
+                }
+
+                let mut writer = sink.open(&meta).await?;
+                let mut size: u64 = 0;
+                let stored = loop {
+                    let chunk = match field.chunk().await {
+                        Ok(Some(chunk)) => chunk,
+                        Ok(None) => break Ok(()),
+                        Err(e) => break Err(invalid(e)),
+                    };
+                    size += chunk.len() as u64;
+                    total += chunk.len() as u64;
+                    if size > limits.max_file_size {
+                        break Err(too_large(&reject, "File exceeds the size limit"));
+                    }
+                    if total > limits.max_total_size {
+                        break Err(too_large(&reject, "Upload exceeds the total size limit"));
+                    }
+                    if let Err(e) = writer.write(&chunk).await {
+                        break Err(e.into());
+                    }
+                };
+
+                if let Err(err) = stored {
+                    writer.abort().await;
+                    return Err(err);
+                }
+                let data = writer.finish().await?;
+                stored_files.files.push(UploadedFile { meta, size, data });
+            }
+            Ok::<(), AppError>(())
+        }
+        .await;
+
+        let files = std::mem::take(&mut stored_files.files);
+        match result {
+            Ok(()) => {
+                uploads.files = files;
+                Ok(uploads)
+            }
+            Err(err) => {
+                for file in files {
+                    sink.discard(file.data).await;
+                }
+                Err(err)
+            }
+        }
+    }
+}
+
+/// Discards stored files when the extractor is dropped mid-request, e.g. on
+/// client disconnect; emptied once the files are handed to the handler.
+struct DiscardGuard<S: UploadSink + 'static>
+where
+    S::Output: 'static,
+{
+    sink: Option<S>,
+    files: Vec<UploadedFile<S::Output>>,
+}
+
+impl<S: UploadSink + 'static> Drop for DiscardGuard<S>
+where
+    S::Output: 'static,
+{
+    fn drop(&mut self) {
+        let files = std::mem::take(&mut self.files);
+        let Some(sink) = self.sink.take().filter(|_| !files.is_empty()) else {
+            return;
+        };
+        // Without a runtime nothing can run `discard`; the files are left behind
+        if let Ok(runtime) = tokio::runtime::Handle::try_current() {
+            runtime.spawn(async move {
+                for file in files {
+                    sink.discard(file.data).await;
+                }
+            });
+        }
+    }
+}
+
+/// Slack for part headers and boundaries on top of `max_total_size`.
+const MULTIPART_OVERHEAD: u64 = 64 * 1024;
+
+fn too_large(reject: &impl Fn(StatusCode, String) -> AppError, message: &str) -> AppError {
+    reject(StatusCode::PAYLOAD_TOO_LARGE, message.to_string())
+}
+
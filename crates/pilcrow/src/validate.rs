@@ -0,0 +1,373 @@
+// ./crates/pilcrow/src/validate.rs
+
+use std::collections::BTreeMap;
+
+use axum::http::StatusCode;
+use axum::response::{IntoResponse, Response};
+use serde::ser::SerializeMap;
+use serde::Serialize;
+use serde_json::Value;
+
+use crate::extract::{RequestMode, SilcrowRequest};
+use crate::response::{html, json};
+
+pub use pilcrow_macros::Validate;
+
+// ════════════════════════════════════════════════════════════
+// 1. The Trait & Error Set
+// ════════════════════════════════════════════════════════════
+
+/// A submission that can check itself; usually derived.
+pub trait Validate {
+    /// The field keys errors may be reported under, in declaration order.
+    fn fields() -> &'static [&'static str]
+    where
+        Self: Sized;
+
+    fn validate(&self) -> Result<(), ValidationErrors>;
+}
+
+/// Per-field messages collected by [`Validate::validate`].
+///
+/// Serializes to an object holding every known field: the first message
+/// of invalid ones, `null` for the rest, so patching it into `s-bind`
+/// slots also clears messages from a previous attempt.
+#[derive(Debug, Clone, Default)]
+pub struct ValidationErrors {
+    fields: Vec<String>,
+    errors: BTreeMap<String, Vec<String>>,
+}
+
+impl ValidationErrors {
+    pub fn new(fields: &[&str]) -> Self {
+        ValidationErrors {
+            fields: fields.iter().map(|f| f.to_string()).collect(),
+            errors: BTreeMap::new(),
+        }
+    }
+
+    pub fn add(&mut self, field: impl Into<String>, message: impl Into<String>) {
+        let field = field.into();
+        if !self.fields.contains(&field) {
+            self.fields.push(field.clone());
+        }
+        self.errors.entry(field).or_default().push(message.into());
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.errors.is_empty()
+    }
+
+    /// The first message for `field`, if it failed.
+    pub fn get(&self, field: &str) -> Option<&str> {
+        self.errors.get(field)?.first().map(String::as_str)
+    }
+
+    /// Every message for `field`, in rule order.
+    pub fn messages(&self, field: &str) -> &[String] {
+        self.errors.get(field).map(Vec::as_slice).unwrap_or(&[])
+    }
+
+    pub fn into_result(self) -> Result<(), ValidationErrors> {
+        if self.is_empty() {
+            Ok(())
+        } else {
+            Err(self)
+        }
+    }
+}
+
+impl Serialize for ValidationErrors {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let mut map = serializer.serialize_map(Some(self.fields.len()))?;
+        for field in &self.fields {
+            map.serialize_entry(field, &self.get(field))?;
+        }
+        map.end()
+    }
+}
+
+impl std::fmt::Display for ValidationErrors {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let mut first = true;
+        for (field, messages) in &self.errors {
+            for message in messages {
+                if !first {
+                    f.write_str("; ")?;
+                }
+                first = false;
+                write!(f, "{field}: {message}")?;
+            }
+        }
+        Ok(())
+    }
+}
+
+// ════════════════════════════════════════════════════════════
+// 2. Re-rendering Invalid Submissions
+// ════════════════════════════════════════════════════════════
+
+/// What a form needs to render itself again: the submitted values and the errors.
+#[derive(Debug, Clone)]
+pub struct FormState {
+    values: BTreeMap<String, String>,
+    errors: ValidationErrors,
+}
+
+impl FormState {
+    /// Reads the submitted values back out of the deserialized form.
+    pub fn new<T: Serialize>(values: &T, errors: ValidationErrors) -> Self {
+        let values = match serde_json::to_value(values) {
+            Ok(Value::Object(map)) => map
+                .into_iter()
+                .map(|(field, value)| (field, input_value(value)))
+                .collect(),
+            _ => BTreeMap::new(),
+        };
+        FormState { values, errors }
+    }
+
+    /// The submitted value of `field`, or `""`.
+    pub fn value(&self, field: &str) -> &str {
+        self.values.get(field).map(String::as_str).unwrap_or("")
+    }
+
+    /// The first message for `field`, if it failed.
+    pub fn error(&self, field: &str) -> Option<&str> {
+        self.errors.get(field)
+    }
+
+    pub fn errors(&self) -> &ValidationErrors {
+        &self.errors
+    }
+}
+
+/// Renders a JSON value the way an `<input value>` would have held it.
+fn input_value(value: Value) -> String {
+    match value {
+        Value::Null => String::new(),
+        Value::String(s) => s,
+        Value::Array(items) => items
+            .into_iter()
+            .map(input_value)
+            .collect::<Vec<_>>()
+            .join(","),
+        other => other.to_string(),
+    }
+}
+
+impl SilcrowRequest {
+    /// Answers a failed validation with 422 Unprocessable Entity.
+    ///
+    /// HTML requests get `render`'s markup, which silcrow.js swaps into the
+    /// form's target like a success; JSON requests get `{"errors": {...}}`
+    /// for `patch()`ing into `s-bind="errors.<field>"` slots.
+    pub fn invalid<T, F, H>(&self, values: &T, errors: ValidationErrors, render: F) -> Response
+    where
+        T: Serialize,
+        F: FnOnce(&FormState) -> H,
+        H: Into<String>,
+    {
+        let mut response = match self.preferred_mode() {
+            RequestMode::Json | RequestMode::Other(_) => {
+                json(serde_json::json!({ "errors": errors })).into_response()
+            }
+            RequestMode::Html | RequestMode::Navigate => {
+                let state = FormState::new(values, errors);
+                html(render(&state)).into_response()
+            }
+        };
+        *response.status_mut() = StatusCode::UNPROCESSABLE_ENTITY;
+        response
+    }
+}
+
+// ════════════════════════════════════════════════════════════
+// 3. Built-in Rules
+// ════════════════════════════════════════════════════════════
+
+/// The checks `#[derive(Validate)]` expands to. `Option` fields are only
+/// checked when present; `required` is what rejects `None`.
+pub mod rules {
+    pub trait Present {
+        fn is_present(&self) -> bool;
+    }
+
+    impl Present for String {
+        fn is_present(&self) -> bool {
+            !self.trim().is_empty()
+        }
+    }
+
+    impl Present for &str {
+        fn is_present(&self) -> bool {
+            !self.trim().is_empty()
+        }
+    }
+
+    impl<T> Present for Vec<T> {
+        fn is_present(&self) -> bool {
+            !self.is_empty()
+        }
+    }
+
+    // A scalar that parsed at all is present; only its `Option` can be missing
+    macro_rules! present {
+        ($($ty:ty),*) => {
+            $(impl Present for $ty {
+                fn is_present(&self) -> bool {
+                    true
+                }
+            })*
+        };
+    }
+    present!(i8, i16, i32, i64, isize, u8, u16, u32, u64, usize, f32, f64, bool, char);
+
+    impl<T: Present> Present for Option<T> {
+        fn is_present(&self) -> bool {
+            self.as_ref().is_some_and(Present::is_present)
+        }
+    }
+
+    /// Values with a length: characters for strings, items for `Vec`.
+    pub trait Length {
+        /// What `length` counts, plural, for error messages.
+        const UNIT: &'static str = "characters";
+
+        fn length(&self) -> Option<usize>;
+    }
+
+    impl Length for String {
+        fn length(&self) -> Option<usize> {
+            Some(self.chars().count())
+        }
+    }
+
+    impl Length for &str {
+        fn length(&self) -> Option<usize> {
+            Some(self.chars().count())
+        }
+    }
+
+    impl<T> Length for Vec<T> {
+        const UNIT: &'static str = "items";
+
+        fn length(&self) -> Option<usize> {
+            Some(self.len())
+        }
+    }
+
+    impl<T: Length> Length for Option<T> {
+        const UNIT: &'static str = T::UNIT;
+
+        fn length(&self) -> Option<usize> {
+            self.as_ref()?.length()
+        }
+    }
+
+    pub trait Text {
+        fn text(&self) -> Option<&str>;
+    }
+
+    impl Text for String {
+        fn text(&self) -> Option<&str> {
+            Some(self)
+        }
+    }
+
+    impl Text for &str {
+        fn text(&self) -> Option<&str> {
+            Some(self)
+        }
+    }
+
+    impl<T: Text> Text for Option<T> {
+        fn text(&self) -> Option<&str> {
+            self.as_ref()?.text()
+        }
+    }
+
+    pub trait Number {
+        fn number(&self) -> Option<f64>;
+    }
+
+    macro_rules! number {
+        ($($ty:ty),*) => {
+            $(impl Number for $ty {
+                fn number(&self) -> Option<f64> {
+                    Some(*self as f64)
+                }
+            })*
+        };
+    }
+    number!(i8, i16, i32, i64, isize, u8, u16, u32, u64, usize, f32, f64);
+
+    impl<T: Number> Number for Option<T> {
+        fn number(&self) -> Option<f64> {
+            self.as_ref()?.number()
+        }
+    }
+
+    pub fn required<T: Present>(value: &T) -> Result<(), String> {
+        if value.is_present() {
+            Ok(())
+        } else {
+            Err("This field is required".into())
+        }
+    }
+
+    pub fn length<T: Length>(value: &T, min: Option<usize>, max: Option<usize>) -> Result<(), String> {
+        let Some(len) = value.length() else {
+            return Ok(());
+        };
+        // "1 item", "2 items"
+        let unit = |n: usize| match n {
+            1 => T::UNIT.strip_suffix('s').unwrap_or(T::UNIT),
+            _ => T::UNIT,
+        };
+        match (min, max) {
+            (Some(min), _) if len < min => Err(format!("Must be at least {min} {}", unit(min))),
+            (_, Some(max)) if len > max => Err(format!("Must be at most {max} {}", unit(max))),
+            _ => Ok(()),
+        }
+    }
+
+    /// A deliberately loose check: one `@`, something before it, and a
+    /// dotted domain after it. Deliverability is the mailer's problem.
+    pub fn email<T: Text>(value: &T) -> Result<(), String> {
+        let Some(text) = value.text() else {
+            return Ok(());
+        };
+        if text.is_empty() {
+            return Ok(());
+        }
+        let valid = match text.split_once('@') {
+            Some((local, domain)) => {
+                !local.is_empty()
+                    && !domain.contains('@')
+                    && !text.chars().any(char::is_whitespace)
+                    && domain
+                        .split_once('.')
+                        .is_some_and(|(host, tld)| !host.is_empty() && !tld.is_empty())
+                    && !domain.ends_with('.')
+            }
+            None => false,
+        };
+        if valid {
+            Ok(())
+        } else {
+            Err("Must be a valid email address".into())
+        }
+    }
+
+    pub fn range<T: Number>(value: &T, min: Option<f64>, max: Option<f64>) -> Result<(), String> {
+        let Some(number) = value.number() else {
+            return Ok(());
+        };
+        match (min, max) {
+            (Some(min), _) if number < min => Err(format!("Must be at least {min}")),
+            (_, Some(max)) if number > max => Err(format!("Must be at most {max}")),
+            _ => Ok(()),
+        }
+    }
+}
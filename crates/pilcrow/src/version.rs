@@ -0,0 +1,161 @@
+// ./crates/pilcrow/src/version.rs
+
+use std::future::Future;
+use std::pin::Pin;
+use std::task::{Context, Poll};
+
+use axum::http::{header, HeaderValue, Request};
+use axum::response::{IntoResponse, Response};
+use tower_layer::Layer;
+use tower_service::Service;
+
+use crate::assets::SILCROW_JS_HASH;
+use crate::extract::SilcrowRequest;
+
+// ════════════════════════════════════════════════════════════
+// 1. Runtime Version
+// ════════════════════════════════════════════════════════════
+
+/// Identifies the client runtime a page was rendered with: the embedded
+/// `silcrow.js` hash plus an optional application version.
+///
+/// [`ScriptTag::app_version`](crate::ScriptTag::app_version) puts the token
+/// on the page and silcrow.js echoes it in `silcrow-runtime`, so a deploy
+/// that changes either part makes open tabs reload.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RuntimeVersion {
+    app: Option<String>,
+}
+
+impl RuntimeVersion {
+    /// Only the embedded bundle hash.
+    pub fn new() -> Self {
+        RuntimeVersion { app: None }
+    }
+
+    /// Folds an application version (build id, git sha...) into the check.
+    pub fn app(mut self, version: impl Into<String>) -> Self {
+        self.app = Some(version.into());
+        self
+    }
+
+    /// The header-safe token silcrow.js sends back.
+    pub fn token(&self) -> String {
+        match &self.app {
+            Some(app) => format!("{SILCROW_JS_HASH}+{}", urlencoding::encode(app)),
+            None => SILCROW_JS_HASH.to_string(),
+        }
+    }
+}
+
+impl Default for RuntimeVersion {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl SilcrowRequest {
+    /// True when silcrow.js reported a runtime token that differs from
+    /// `expected`. Requests without a token (hard loads, API clients, pages
+    /// rendered before the handshake existed) are never stale.
+    pub fn is_stale(&self, expected: &RuntimeVersion) -> bool {
+        self.runtime
+            .as_deref()
+            .is_some_and(|token| token != expected.token())
+    }
+}
+
+// ════════════════════════════════════════════════════════════
+// 2. Reload Directive
+// ════════════════════════════════════════════════════════════
+
+/// Tells silcrow.js to drop the swap and do a full page load instead.
+pub struct ReloadResponse;
+
+pub fn reload() -> ReloadResponse {
+    ReloadResponse
+}
+
+impl IntoResponse for ReloadResponse {
+    fn into_response(self) -> Response {
+        let mut response = ().into_response();
+        let headers = response.headers_mut();
+        headers.insert("silcrow-reload", HeaderValue::from_static("true"));
+        headers.insert(header::CACHE_CONTROL, HeaderValue::from_static("no-store"));
+        response
+    }
+}
+
+// ════════════════════════════════════════════════════════════
+// 3. Middleware
+// ════════════════════════════════════════════════════════════
+
+/// Answers stale Silcrow requests with [`reload`] before they reach a
+/// handler, so an old runtime never processes new responses (or submits
+/// mutations against them).
+#[derive(Debug, Clone)]
+pub struct VersionCheckLayer {
+    /// `None` passes every request through; how `SilcrowLayer` turns it off.
+    expected: Option<RuntimeVersion>,
+}
+
+impl VersionCheckLayer {
+    pub fn new(expected: RuntimeVersion) -> Self {
+        VersionCheckLayer {
+            expected: Some(expected),
+        }
+    }
+
+    pub(crate) fn disabled() -> Self {
+        VersionCheckLayer { expected: None }
+    }
+}
+
+impl Default for VersionCheckLayer {
+    fn default() -> Self {
+        VersionCheckLayer::new(RuntimeVersion::default())
+    }
+}
+
+impl<S> Layer<S> for VersionCheckLayer {
+    type Service = VersionCheckService<S>;
+
+    fn layer(&self, inner: S) -> Self::Service {
+        VersionCheckService {
+            inner,
+            token: self.expected.as_ref().map(RuntimeVersion::token),
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct VersionCheckService<S> {
+    inner: S,
+    token: Option<String>,
+}
+
+impl<S, B> Service<Request<B>> for VersionCheckService<S>
+where
+    S: Service<Request<B>, Response = Response>,
+    S::Future: Send + 'static,
+{
+    type Response = Response;
+    type Error = S::Error;
+    type Future = Pin<Box<dyn Future<Output = Result<Response, S::Error>> + Send>>;
+
+    fn poll_ready(&mut self, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        self.inner.poll_ready(cx)
+    }
+
+    fn call(&mut self, request: Request<B>) -> Self::Future {
+        let stale = self.token.as_ref().is_some_and(|expected| {
+            SilcrowRequest::from_headers(request.headers())
+                .runtime
+                .is_some_and(|token| token != *expected)
+        });
+        if stale {
+            return Box::pin(async { Ok(reload().into_response()) });
+        }
+        Box::pin(self.inner.call(request))
+    }
+}